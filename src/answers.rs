@@ -0,0 +1,109 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+
+use crate::input::{InputSource, year_file_names};
+
+// Known correct answers for a day, as stored in `input/YYYY/NN.ans`. Line N of the file holds the
+// answer to part N, with backslashes escaped as `\\` and newlines as `\n` so that multi-line
+// answers (like day 10's) fit on a single line. Empty or absent lines mean the answer is unknown.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers(Vec<Option<String>>);
+
+impl Answers {
+    // Reads the answers from the same directory as the cached inputs.
+    pub fn load(source: &InputSource, year: u32, day: u32) -> Result<Answers, io::Error> {
+        for file_name in year_file_names(&source.cache_dir, year, &format!("{:02}.ans", day)) {
+            match fs::read_to_string(file_name) {
+                Ok(contents) => return Ok(Answers::parse(&contents)),
+                Err(ref err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Answers::default())
+    }
+
+    pub fn parse(contents: &str) -> Answers {
+        Answers(contents
+            .lines()
+            .map(|line| if line.is_empty() { None } else { Some(unescape(line)) })
+            .collect())
+    }
+
+    pub fn get(&self, part: u32) -> Option<&str> {
+        self.0.get(part as usize - 1)?.as_ref().map(String::as_str)
+    }
+
+    pub fn check(&self, part: u32, output: &str) -> Check {
+        match self.get(part) {
+            Some(expected) if expected == output => Check::Pass,
+            Some(expected) => Check::Fail(expected.to_string()),
+            None => Check::Missing,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail(String),
+    Missing,
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Check::Pass => write!(f, "PASS"),
+            Check::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Check::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+pub fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[test]
+fn test_escape_roundtrip() {
+    for answer in &["580", "mphcuasvrnjzzkbgdtqeoylva", "\n#..#\n####", "a\\nb", "\\"] {
+        assert!(!escape(answer).contains('\n'));
+        assert_eq!(unescape(&escape(answer)), *answer);
+    }
+}
+
+#[test]
+fn test_answers_check() {
+    let answers = Answers::parse("42\n\n\\n#.#\n");
+    assert_eq!(answers.check(1, "42"), Check::Pass);
+    assert_eq!(answers.check(1, "43"), Check::Fail("42".to_string()));
+    assert_eq!(answers.check(2, "42"), Check::Missing);
+    assert_eq!(answers.check(3, "\n#.#"), Check::Pass);
+    assert_eq!(answers.check(4, "42"), Check::Missing);
+}
+
+#[test]
+fn test_answers_load() {
+    let source = crate::input::test_source("answers", "http://unused");
+    assert_eq!(Answers::load(&source, 2019, 2).unwrap(), Answers::default());
+    fs::create_dir_all(source.cache_dir.join("2019")).unwrap();
+    fs::write(source.cache_dir.join("2019/02.ans"), "7\n").unwrap();
+    assert_eq!(Answers::load(&source, 2019, 2).unwrap().check(1, "7"), Check::Pass);
+}
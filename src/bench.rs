@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Summary statistics over repeated runs of the same part, in microseconds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1e6 + f64::from(duration.subsec_nanos()) / 1e3
+}
+
+impl Stats {
+    pub fn from_durations(durations: &[Duration]) -> Stats {
+        assert!(!durations.is_empty(), "no durations to compute statistics over");
+        let mut values: Vec<f64> = durations.iter().map(|&d| micros(d)).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let runs = values.len();
+        let median = if runs % 2 == 1 {
+            values[runs / 2]
+        } else {
+            (values[runs / 2 - 1] + values[runs / 2]) / 2.0
+        };
+        let mean = values.iter().sum::<f64>() / runs as f64;
+        let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / runs as f64;
+        Stats { runs, min: values[0], median, mean, stddev: variance.sqrt() }
+    }
+
+    pub fn median_duration(&self) -> Duration {
+        Duration::from_nanos((self.median * 1e3).round() as u64)
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} runs: min {:.1} us, median {:.1} us, mean {:.1} us, stddev {:.1} us",
+            self.runs, self.min, self.median, self.mean, self.stddev)
+    }
+}
+
+const CSV_HEADER: &str = "timestamp,year,day,part,runs,min_us,median_us,mean_us,stddev_us";
+
+// Appends one row per benchmarked part to a CSV file, writing a header first if the file is new.
+// Rows are timestamped so that results from different commits can be compared.
+pub fn append_csv(path: &Path, rows: &[(u32, u32, u32, Stats)]) -> Result<(), io::Error> {
+    let is_new = !path.exists();
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    for (year, day, part, stats) in rows {
+        writeln!(file, "{},{},{},{},{},{:.3},{:.3},{:.3},{:.3}",
+            timestamp, year, day, part, stats.runs, stats.min, stats.median, stats.mean, stats.stddev)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stats() {
+    let stats = Stats::from_durations(&[
+        Duration::from_micros(4),
+        Duration::from_micros(2),
+        Duration::from_micros(6),
+        Duration::from_micros(4),
+    ]);
+    assert_eq!(stats, Stats { runs: 4, min: 2.0, median: 4.0, mean: 4.0, stddev: 2.0f64.sqrt() });
+    assert_eq!(stats.median_duration(), Duration::from_micros(4));
+
+    let stats = Stats::from_durations(&[Duration::from_nanos(1500), Duration::from_nanos(500), Duration::from_secs(1)]);
+    assert_eq!(stats.min, 0.5);
+    assert_eq!(stats.median, 1.5);
+}
+
+#[test]
+fn test_append_csv() {
+    let path = std::env::temp_dir().join(format!("aoc-bench-test-{}.csv", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let stats = Stats { runs: 3, min: 1.0, median: 2.0, mean: 2.5, stddev: 0.5 };
+    append_csv(&path, &[(2018, 1, 1, stats.clone())]).unwrap();
+    append_csv(&path, &[(2018, 1, 2, stats)]).unwrap();
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], CSV_HEADER);
+    assert!(lines[1].ends_with(",2018,1,1,3,1.000,2.000,2.500,0.500"));
+    assert!(lines[2].ends_with(",2018,1,2,3,1.000,2.000,2.500,0.500"));
+    fs::remove_file(&path).unwrap();
+}
@@ -1,25 +1,28 @@
+use aoc::{Error, parse_lines};
 use std::collections::HashSet;
 
-fn part1(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|line| line.parse::<i32>().unwrap())
-        .sum::<i32>()
+fn parse_input(input: &str) -> Result<Vec<i32>, Error> {
+    parse_lines(input, str::parse::<i32>)
+}
+
+pub fn part1(input: &str) -> Result<i32, Error> {
+    Ok(parse_input(input)?.iter().sum())
 }
 
 #[test]
 fn part1examples() {
-    assert_eq!(part1("+1\n-2\n+3\n+1"), 3);
-    assert_eq!(part1("+1\n+1\n+1"), 3);
-    assert_eq!(part1("+1\n+1\n-2"), 0);
-    assert_eq!(part1("-1\n-2\n-3"), -6);
+    assert_eq!(part1("+1\n-2\n+3\n+1"), Ok(3));
+    assert_eq!(part1("+1\n+1\n+1"), Ok(3));
+    assert_eq!(part1("+1\n+1\n-2"), Ok(0));
+    assert_eq!(part1("-1\n-2\n-3"), Ok(-6));
+    assert_eq!(part1("+1\nfoo").unwrap_err().line_number(), Some(2));
 }
 
-fn part2(input: &str) -> i32 {
-    let freqs: Vec<i32> = input
-        .lines()
-        .map(|line| line.parse::<i32>().unwrap())
-        .collect();
+pub fn part2(input: &str) -> Result<i32, Error> {
+    let freqs = parse_input(input)?;
+    if freqs.is_empty() {
+        return Err(Error::new("no frequency changes in input"));
+    }
     let mut sum: i32 = 0;
     let mut seen = HashSet::new();
     loop {
@@ -27,7 +30,7 @@ fn part2(input: &str) -> i32 {
             seen.insert(sum);
             sum += freq;
             if seen.contains(&sum) {
-                return sum;
+                return Ok(sum);
             }
         }
     }
@@ -35,12 +38,13 @@ fn part2(input: &str) -> i32 {
 
 #[test]
 fn part2example() {
-    assert_eq!(part2("+1\n-2\n+3\n+1"), 2);
-    assert_eq!(part2("+3\n+3\n+4\n-2\n-4"), 10);
-    assert_eq!(part2("-6\n+3\n+8\n+5\n-6"), 5);
-    assert_eq!(part2("+7\n+7\n-2\n-7\n-4"), 14);
+    assert_eq!(part2("+1\n-2\n+3\n+1"), Ok(2));
+    assert_eq!(part2("+3\n+3\n+4\n-2\n-4"), Ok(10));
+    assert_eq!(part2("-6\n+3\n+8\n+5\n-6"), Ok(5));
+    assert_eq!(part2("+7\n+7\n-2\n-7\n-4"), Ok(14));
+    assert!(part2("").is_err());
 }
 
 fn main() {
-    aoc::main(part1, part2);
+    aoc::try_main(part1, part2);
 }
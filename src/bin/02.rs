@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let (twos, threes) = input.lines().fold((0, 0), |state, id| {
         let mut counts = HashMap::new();
         id.chars().for_each(|letter| *counts.entry(letter).or_insert(0) += 1);
@@ -17,7 +17,7 @@ fn part1example() {
     assert_eq!(part1("abcdef\nbababc\nabbcde\nabcccd\naabcdd\nabcdee\nababab"), 12);
 }
 
-fn part2(input: &str) -> String {
+pub fn part2(input: &str) -> String {
     let mut counts = HashMap::new();
     for id in input.lines() {
         (0..id.len()).for_each(|skip_index| {
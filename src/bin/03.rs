@@ -1,73 +1,59 @@
-use std::collections::HashMap;
+use aoc::{Error, parse_lines};
+use aoc::geom::Rect;
 use std::collections::HashSet;
 use regex::Regex;
 
 struct Claim {
     id: u32,
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
+    rect: Rect,
 }
 
-fn parse_input<'a>(input: &'a str) -> Box<impl Iterator<Item=Claim> + 'a> {
+fn parse_input(input: &str) -> Result<Vec<Claim>, Error> {
     let re = Regex::new(r"^#(\d+) @ (\d+),(\d+): (\d+)x(\d+)$").unwrap();
-    Box::new(input
-        .lines()
-        .map(move |line| {
-            let captures = re.captures(&line).unwrap();
-            Claim {
-                id: captures.get(1).unwrap().as_str().parse::<u32>().unwrap(),
-                x: captures.get(2).unwrap().as_str().parse::<u32>().unwrap(),
-                y: captures.get(3).unwrap().as_str().parse::<u32>().unwrap(),
-                w: captures.get(4).unwrap().as_str().parse::<u32>().unwrap(),
-                h: captures.get(5).unwrap().as_str().parse::<u32>().unwrap(),
-            }
-        }))
+    parse_lines(input, |line| -> Result<Claim, Error> {
+        let captures = re.captures(line).ok_or_else(|| Error::new("expected a claim like #1 @ 2,3: 4x5"))?;
+        let x = captures[2].parse::<i32>()?;
+        let y = captures[3].parse::<i32>()?;
+        let w = captures[4].parse::<i32>()?;
+        let h = captures[5].parse::<i32>()?;
+        Ok(Claim {
+            id: captures[1].parse::<u32>()?,
+            rect: Rect::from_exclusive_ranges(x..(x + w), y..(y + h)),
+        })
+    })
 }
 
-fn part1(input: &str) -> usize {
-    let mut counts = HashMap::new();
-    for claim in parse_input(input) {
-        for cy in claim.x..(claim.x + claim.w) {
-            for cx in claim.y..(claim.y + claim.h) {
-                let key = (cx, cy);
-                *counts.entry(key).or_insert(0) += 1;
-            }
+pub fn part1(input: &str) -> Result<usize, Error> {
+    let claims = parse_input(input)?;
+    let mut overlapping = HashSet::new();
+    for (i, a) in claims.iter().enumerate() {
+        for b in &claims[i + 1..] {
+            overlapping.extend(a.rect.intersection(&b.rect));
         }
     }
-    counts.values().filter(|c| **c > 1).count()
+    Ok(overlapping.len())
 }
 
 #[test]
 fn part1example() {
-    assert_eq!(part1("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"), 4);
+    assert_eq!(part1("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"), Ok(4));
+    assert_eq!(part1("#1 @ 1,3: 4x4\n#2 @ 3,1 4x4").unwrap_err().line_number(), Some(2));
 }
 
-fn part2(input: &str) -> u32 {
-    let mut candidate_ids = HashSet::new();
-    let mut ids = HashMap::new();
-    for claim in parse_input(input) {
-        candidate_ids.insert(claim.id);
-        for cy in claim.x..(claim.x + claim.w) {
-            for cx in claim.y..(claim.y + claim.h) {
-                let key = (cx, cy);
-                if let Some(other_id) = ids.get(&key) {
-                    candidate_ids.remove(&claim.id);
-                    candidate_ids.remove(&other_id);
-                }
-                ids.insert(key, claim.id);
-            }
-        }
-    }
-    candidate_ids.into_iter().next().unwrap()
+pub fn part2(input: &str) -> Result<u32, Error> {
+    let claims = parse_input(input)?;
+    claims.iter()
+        .find(|claim| !claims.iter().any(|other| other.id != claim.id && other.rect.overlaps(&claim.rect)))
+        .map(|claim| claim.id)
+        .ok_or_else(|| Error::new("every claim overlaps another"))
 }
 
 #[test]
 fn part2example() {
-    assert_eq!(part2("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"), 3);
+    assert_eq!(part2("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2"), Ok(3));
+    assert!(part2("#1 @ 1,3: 4x4\n#2 @ 1,3: 4x4").is_err());
 }
 
 fn main() {
-    aoc::main(part1, part2);
+    aoc::try_main(part1, part2);
 }
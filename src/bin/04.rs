@@ -85,7 +85,7 @@ fn parse_input(input: &str) -> HashMap<u32, Guard> {
     guards
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let guards = parse_input(input);
     let guard = guards
         .values()
@@ -99,7 +99,7 @@ fn part1example() {
     assert_eq!(part1(EXAMPLE), 240);
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let guards = parse_input(input);
     let guard = guards
         .values()
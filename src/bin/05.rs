@@ -18,7 +18,7 @@ fn remove_unit(input: impl Iterator<Item=u8>, removal: u8) -> impl Iterator<Item
     input.filter(move |c| !c.eq_ignore_ascii_case(&removal))
 }
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     reacted_length(input.bytes())
 }
 
@@ -31,7 +31,7 @@ fn part1examples() {
     assert_eq!(part1("dabAcCaCBAcCcaDA"), 10);
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     (b'A'..(b'Z' + 1))
         .map(|removal| remove_unit(input.bytes(), removal))
         .map(reacted_length)
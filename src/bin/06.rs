@@ -40,7 +40,7 @@ fn parse_input(input: &str) -> Vec<Point> {
         .collect()
 }
 
-fn part1(input: &str) -> i32 {
+pub fn part1(input: &str) -> i32 {
     let points = parse_input(input);
     let x_min = points.iter().map(|p| p.x).min().unwrap();
     let x_max = points.iter().map(|p| p.x).max().unwrap();
@@ -105,7 +105,7 @@ fn part2_with_threshold(input: &str, threshold: i32) -> u32 {
     safe_region_size
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     part2_with_threshold(input, 10000)
 }
 
@@ -38,7 +38,7 @@ fn parse_tree(input: &str) -> Node {
     node
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let tree = parse_tree(input);
     tree.metadata_sum()
 }
@@ -48,7 +48,7 @@ fn part1example() {
     assert_eq!(part1("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2"), 138);
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let tree = parse_tree(input);
     tree.value()
 }
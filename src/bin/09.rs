@@ -146,7 +146,7 @@ fn parse_input(input: &str) -> (usize, usize) {
     )
 }
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let (num_players, last_marble_value) = parse_input(input);
     winning_score(num_players, last_marble_value)
 }
@@ -189,7 +189,7 @@ fn part1examples() {
     assert_eq!(part1("30 players; last marble is worth 5807 points"), 37305);
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     let (num_players, last_marble_value) = parse_input(input);
     winning_score(num_players, 100 * last_marble_value)
 }
@@ -78,8 +78,10 @@ fn render(points: &Vec<Point>) -> String {
     let bounding_box: Rect = points.iter().collect();
     let row = vec![false; bounding_box.width() as usize];
     let mut out: Vec<Vec<bool>> = vec![row; bounding_box.height() as usize];
-    for point in points {
-        out[(point.y - bounding_box.y_min()) as usize][(point.x - bounding_box.x_min()) as usize] = true;
+    let top_left = Point::new(bounding_box.x_min(), bounding_box.y_min());
+    for &point in points {
+        let offset = point - top_left;
+        out[offset.y as usize][offset.x as usize] = true;
     }
     "\n".to_string() + &out.iter().map(|row| row.iter().map(|&cell| if cell { '#' } else { '.' }).join("")).join("\n")
 }
@@ -105,7 +107,7 @@ fn message_time(stars: &Vec<Star>) -> i32 {
     panic!();
 }
 
-fn part1(input: &str) -> String {
+pub fn part1(input: &str) -> String {
     let stars = parse_input(input);
     render(&simulate(&stars, message_time(&stars)))
 }
@@ -122,7 +124,7 @@ fn part1example() {
 #...#..###");
 }
 
-fn part2(input: &str) -> i32 {
+pub fn part2(input: &str) -> i32 {
     let stars = parse_input(input);
     message_time(&stars)
 }
@@ -14,16 +14,25 @@ fn test_power_level() {
     assert_eq!(power_level(Point::new(101, 153), 71), 4);
 }
 
-fn part1(input: &str) -> Point {
+fn power_levels(serial_number: i32) -> Matrix<i32> {
+    let rect = Rect::from_inclusive_ranges(1 ..= 300, 1 ..= 300);
+    let mut levels = Matrix::new(&rect, 0);
+    for cell in rect {
+        levels[cell] = power_level(cell, serial_number);
+    }
+    levels
+}
+
+fn square(cell: Point, size: i32) -> Rect {
+    Rect::from_exclusive_ranges(cell.x .. cell.x + size, cell.y .. cell.y + size)
+}
+
+pub fn part1(input: &str) -> Point {
     let serial_number = input.trim().parse::<i32>().unwrap();
+    let sums = power_levels(serial_number).summed_area();
     Rect::from_inclusive_ranges(1 ..= 300 - 2, 1 ..= 300 - 2)
         .iter()
-        .max_by_key(|cell| {
-            Rect::from_inclusive_ranges(cell.x ..= cell.x + 2, cell.y ..= cell.y + 2)
-                .iter()
-                .map(|cell| power_level(cell, serial_number))
-                .sum::<i32>()
-        })
+        .max_by_key(|&cell| sums.rect_sum(&square(cell, 3)))
         .unwrap()
 }
 
@@ -33,21 +42,12 @@ fn part1example() {
     assert_eq!(part1("42\n"), Point::new(21, 61));
 }
 
-fn part2(input: &str) -> String {
+pub fn part2(input: &str) -> String {
     let serial_number = input.trim().parse::<i32>().unwrap();
+    let sums = power_levels(serial_number).summed_area();
 
-    // Compute cumulative sum of all cells in the rectangle from 1,1 to x,y exclusive. O(n²).
-    let mut cum_sum = Matrix::new(&Rect::from_inclusive_ranges(1 ..= 301, 1 ..= 301), 0);
-    for cell in Rect::from_inclusive_ranges(1 ..= 300, 1 ..= 300) {
-        cum_sum[cell + Point::new(1, 1)] =
-            power_level(cell, serial_number)
-            + cum_sum[cell + Point::new(0, 1)]
-            + cum_sum[cell + Point::new(1, 0)]
-            - cum_sum[cell];
-    }
-
-    // Use cumulative sums to quickly compute the total for each possible square. O(n³). I'm not
-    // sure that a more efficient approach is possible.
+    // The summed-area table gives the total for each possible square in O(1), so this is O(n³).
+    // I'm not sure that a more efficient approach is possible.
     let all_cells = Rect::from_inclusive_ranges(1 ..= 300, 1 ..= 300);
     let (cell, size) =
         all_cells
@@ -56,12 +56,7 @@ fn part2(input: &str) -> String {
         .filter(|(cell, size)| {
             all_cells.contains(*cell + Point::new(*size, *size))
         })
-        .max_by_key(|(cell, size)| {
-            cum_sum[*cell + Point::new(*size, *size)]
-            - cum_sum[*cell + Point::new(0, *size)]
-            - cum_sum[*cell + Point::new(*size, 0)]
-            + cum_sum[*cell]
-        })
+        .max_by_key(|&(cell, size)| sums.rect_sum(&square(cell, size)))
         .unwrap();
     format!("{},{}", cell, size)
 }
@@ -1,3 +1,5 @@
+use aoc::cycle;
+use aoc::geom::{GrowableMatrix, Matrix, Point, Rect};
 use std::collections::HashMap;
 
 #[allow(dead_code)]
@@ -20,64 +22,59 @@ static EXAMPLE: &str = "initial state: #..#.#..##......###...###
 
 type Rules = HashMap<Vec<u8>, u8>;
 
+const EMPTY: u8 = b'.';
+const PLANT: u8 = b'#';
+
+// The pots are a single-row matrix at y = 0, trimmed to the leftmost and rightmost plant.
+// The number of the pot at x is x + offset.
 struct State {
-    start: i64,
-    pots: Vec<u8>,
+    offset: i64,
+    pots: GrowableMatrix<u8>,
 }
 
 impl State {
     fn next(&self, rules: &Rules) -> State {
-        let next_start = self.start - 2;
-        let next_pots =
-            (-2 .. self.pots.len() as i32 + 2)
-            .map(|idx| {
-                let segment: Vec<u8> =
-                    (-2 ..= 2)
-                    .map(|offset| {
-                        *self.pots.get((idx + offset) as usize).unwrap_or(&('.' as u8))
-                    })
-                    .collect();
-                *rules.get(&segment).unwrap_or(&('.' as u8))
-            })
-            .collect();
-        State { start: next_start, pots: next_pots }
+        let rect = self.pots.rect();
+        let mut next_pots = GrowableMatrix::new(&Rect::empty(), EMPTY);
+        for x in rect.x_min() - 2 ..= rect.x_max() + 2 {
+            let segment: Vec<u8> = (x - 2 ..= x + 2).map(|x| self.pots[Point::new(x, 0)]).collect();
+            let pot = *rules.get(&segment).unwrap_or(&EMPTY);
+            if pot != EMPTY {
+                next_pots[Point::new(x, 0)] = pot;
+            }
+        }
+        next_pots.shrink_to_fit(|&pot| pot == EMPTY);
+        State { offset: self.offset, pots: next_pots }
     }
 
-    fn value(&self) -> i64 {
-        self.pots
-            .iter()
-            .enumerate()
-            .map(|(idx, &pot)| if pot == '#' as u8 { self.start + idx as i64 } else { 0 })
-            .sum()
+    fn row(&self) -> &[u8] {
+        if self.pots.rect().is_empty() { &[] } else { self.pots.row(0) }
     }
 
-    fn trimmed(self) -> State {
-        let left_trim = self.pots
-            .iter()
-            .take_while(|&&pot| pot != '#' as u8)
-            .count();
-        let right_trim = self.pots
-            .iter()
-            .rev()
-            .take_while(|&&pot| pot != '#' as u8)
-            .count();
-        State {
-            start: self.start + left_trim as i64,
-            pots: self.pots[left_trim .. self.pots.len() - right_trim].to_vec()
-        }
+    fn start(&self) -> i64 {
+        i64::from(self.pots.rect().x_min()) + self.offset
+    }
+
+    fn value(&self) -> i64 {
+        self.pots.coords()
+            .filter(|&p| self.pots[p] == PLANT)
+            .map(|p| i64::from(p.x) + self.offset)
+            .sum()
     }
 }
 
 impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(f, "{:6} {}", self.start, String::from_utf8(self.pots.clone()).unwrap())?;
+        write!(f, "{:6} {}", self.start(), String::from_utf8(self.row().to_vec()).unwrap())?;
         Ok(())
     }
 }
 
 fn parse_input(input: &str) -> (State, Rules) {
     let mut lines = input.lines();
-    let pots = lines.next().unwrap().split(":").skip(1).next().unwrap().trim().bytes().collect();
+    let pots = std::iter::once(lines.next().unwrap().split(":").skip(1).next().unwrap().trim())
+        .collect::<Matrix<u8>>()
+        .into_growable(EMPTY);
     lines.next().unwrap();
     let rules =
         lines
@@ -89,10 +86,10 @@ fn parse_input(input: &str) -> (State, Rules) {
             (pattern, result)
         })
         .collect();
-    (State { start: 0, pots: pots }, rules)
+    (State { offset: 0, pots }, rules)
 }
 
-fn part1(input: &str) -> i64 {
+pub fn part1(input: &str) -> i64 {
     let (initial_state, rules) = parse_input(input);
     let mut state = initial_state;
     for _ in 0..20 {
@@ -106,32 +103,15 @@ fn part1example() {
     assert_eq!(part1(EXAMPLE), 325);
 }
 
-fn part2(input: &str) -> i64 {
-    let end_generation = 50000000000;
+pub fn part2(input: &str) -> i64 {
     let (initial_state, rules) = parse_input(input);
-    let mut state = initial_state;
-    // println!("{:3}: {}", 0, state);
-    let mut patterns_seen: HashMap<Vec<u8>, (i64, u64)> = HashMap::new();
-    let mut generation = 0u64;
-    loop {
-        patterns_seen.insert(state.pots.clone(), (state.start, generation));
-        state = state.next(&rules).trimmed();
-        generation += 1;
-        // println!("{:3}: {}", generation, state);
-        if let Some((prev_start, prev_generation)) = patterns_seen.get(&state.pots) {
-            // Cycle detected!
-            let cycle_length = generation - prev_generation;
-            let start_delta = state.start - prev_start;
-            let remaining_cycles = (end_generation - generation) / cycle_length;
-            generation += remaining_cycles * cycle_length;
-            state.start += remaining_cycles as i64 * start_delta;
-            break;
-        }
-    }
-    while generation < end_generation {
-        state = state.next(&rules).trimmed();
-        generation += 1;
-    }
+    // The pattern eventually repeats, shifted some number of pots along.
+    let state = cycle::fast_forward_translated(
+        initial_state,
+        |state| state.next(&rules),
+        50000000000,
+        |state| (state.row().to_vec(), state.start()),
+        |mut state, delta| { state.offset += delta; state });
     state.value()
 }
 
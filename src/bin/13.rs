@@ -2,40 +2,24 @@ use aoc::geom::{Direction, Matrix, Point, Turn};
 use std::collections::HashMap;
 use std::cmp::Ordering;
 
-static TURNS: &[Turn] = &[
-    Turn::Left,
-    Turn::Straight,
-    Turn::Right,
-];
-
 type Map = Matrix<u8>;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Cart {
     pos: Point,
     direction: Direction,
-    next_turn: usize,
+    next_turn: Turn,
 }
 
 impl Cart {
     fn next(&mut self, map: &Map) {
         self.pos += self.direction.as_point();
         self.direction = match map[self.pos] as char {
-            '/' => match self.direction {
-                Direction::Left => Direction::Down,
-                Direction::Right => Direction::Up,
-                Direction::Up => Direction::Right,
-                Direction::Down => Direction::Left,
-            }
-            '\\' => match self.direction {
-                Direction::Left => Direction::Up,
-                Direction::Right => Direction::Down,
-                Direction::Up => Direction::Left,
-                Direction::Down => Direction::Right,
-            }
+            '/' => self.direction + if self.direction.is_horizontal() { Turn::Left } else { Turn::Right },
+            '\\' => self.direction + if self.direction.is_horizontal() { Turn::Right } else { Turn::Left },
             '+' => {
-                let direction = self.direction + TURNS[self.next_turn];
-                self.next_turn = (self.next_turn + 1) % TURNS.len();
+                let direction = self.direction + self.next_turn;
+                self.next_turn = self.next_turn.next_intersection().unwrap();
                 direction
             },
             _ => self.direction,
@@ -61,23 +45,19 @@ impl Ord for Cart {
 }
 
 fn parse_input(input: &str) -> (Map, Vec<Cart>) {
-    let mut map: Map = input.lines().collect();
-    let mut carts = vec![];
-    for pos in map.coords() {
-        let (direction, track) = match map[pos] as char {
-            '<' => (Direction::Left, '-'),
-            '>' => (Direction::Right, '-'),
-            '^' => (Direction::Up, '|'),
-            'v' => (Direction::Down, '|'),
-            _ => continue
-        };
-        map[pos] = track as u8;
-        carts.push(Cart { pos: pos, direction: direction, next_turn: 0 });
-    }
+    let mut map = Map::parse(input, Point::origin(), b' ');
+    let carts = map
+        .extract_markers(|&cell| {
+            Direction::from_arrow(cell as char)
+                .map(|direction| (direction, if direction.is_horizontal() { b'-' } else { b'|' }))
+        })
+        .into_iter()
+        .map(|(pos, direction)| Cart { pos, direction, next_turn: Turn::Left })
+        .collect();
     (map, carts)
 }
 
-fn part1(input: &str) -> Point {
+pub fn part1(input: &str) -> Point {
     let (map, mut carts) = parse_input(input);
     // We want to be able to borrow a single cart mutably, but also iterate immutably over the
     // vector at the same time.
@@ -109,7 +89,7 @@ fn part1examples() {
 "), Point::new(7, 3));
 }
 
-fn part2(input: &str) -> Point {
+pub fn part2(input: &str) -> Point {
     let (map, mut carts) = parse_input(input);
     // We want to be able to borrow a single cart mutably, but also iterate immutably over the
     // vector at the same time.
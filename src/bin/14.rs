@@ -32,7 +32,7 @@ impl State {
     }
 }
 
-fn part1(input: &str) -> String {
+pub fn part1(input: &str) -> String {
     let num_recipes_to_make = input.trim().parse::<usize>().unwrap();
     let mut state = State::new();
     while state.scoreboard.len() < num_recipes_to_make + 10 {
@@ -49,7 +49,7 @@ fn part1examples() {
     assert_eq!(part1("2018"), "5941429882");
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     let search_string: Vec<u8> = input.trim().chars().map(|c| c as u8 - '0' as u8).collect();
     let search_len = search_string.len();
 
@@ -1,6 +1,6 @@
 use aoc::geom::*;
+use aoc::search::GridSearch;
 use itertools::Itertools;
-use std::collections::VecDeque;
 
 type Map = Matrix<u8>;
 
@@ -33,11 +33,11 @@ fn is_enemy(a: u8, b: u8) -> bool {
 }
 
 impl Unit {
-    fn move_pos(&self, map: &Map) -> Option<Point> {
+    fn move_pos(&self, map: &Map, search: &mut GridSearch) -> Option<Point> {
         if self.find_enemy_in_range(self.pos, map).is_some() {
             None
         } else {
-            self.find_first_step(map)
+            self.find_first_step(map, search)
         }
     }
 
@@ -58,48 +58,32 @@ impl Unit {
             .map(|neighbor| *neighbor)
     }
 
-    fn find_first_step(&self, map: &Map) -> Option<Point> {
-        let mut visited = Matrix::new(map.rect(), false);
-        let mut queue = VecDeque::new();
-
-        visited[self.pos] = true;
-        for &neighbor in self.pos.neighbors().iter() {
-            queue.push_back((neighbor, neighbor, 0));
-        }
-
-        while let Some((pos, first_step, dist)) = queue.pop_front() {
-            if visited[pos] {
-                continue;
-            }
-            if map[pos] != '.' as u8 {
-                continue;
-            }
-            visited[pos] = true;
-            if self.find_enemy_in_range(pos, map).is_some() {
-                return Some(first_step);
-            }
-            for &neighbor in pos.neighbors().iter() {
-                queue.push_back((neighbor, first_step, dist + 1));
-            }
-        }
-        None
+    // Moves towards the nearest square in range of an enemy, in reading order, along the first
+    // step in reading order among the shortest paths to it.
+    fn find_first_step(&self, map: &Map, search: &mut GridSearch) -> Option<Point> {
+        let nearest = search.nearest_targets(
+            self.pos,
+            |pos| map[pos] == b'.',
+            |pos| self.find_enemy_in_range(pos, map).is_some(),
+        )?;
+        nearest.best().1
     }
 }
 
 #[test]
 fn test_unit_move_pos() {
-    let state = parse_input("#######
+    let mut state = parse_input("#######
 #E..G.#
 #...#.#
 #.G.#G#
 #######");
-    assert_eq!(state.units[0].move_pos(&state.map), Some(Point::new(2, 1)));
-    let state = parse_input("#######
+    assert_eq!(state.units[0].move_pos(&state.map, &mut state.search), Some(Point::new(2, 1)));
+    let mut state = parse_input("#######
 #.E...#
 #.....#
 #...G.#
 #######");
-    assert_eq!(state.units[0].move_pos(&state.map), Some(Point::new(3, 1)));
+    assert_eq!(state.units[0].move_pos(&state.map, &mut state.search), Some(Point::new(3, 1)));
 }
 
 #[test]
@@ -128,6 +112,8 @@ struct State {
     map: Map,
     units: Vec<Unit>,
     rounds_completed: u32,
+    // Reused for every move, since the map's bounds never change.
+    search: GridSearch,
 }
 
 impl State {
@@ -159,7 +145,7 @@ impl State {
     }
 
     fn perform_move(&mut self, id: UnitId) {
-        if let Some(new_pos) = self.units[id].move_pos(&self.map) {
+        if let Some(new_pos) = self.units[id].move_pos(&self.map, &mut self.search) {
             let unit = &mut self.units[id];
             self.map[unit.pos] = '.' as u8;
             unit.pos = new_pos;
@@ -499,22 +485,18 @@ fn test_state_round_example_1() {
 }
 
 fn parse_input(input: &str) -> State {
-    let map: Map = input.lines().collect();
+    let map = Map::parse(input, Point::origin(), b' ');
+    // Units stay on the map too, so that they block each other's paths.
     let units = map
-        .coords()
-        .filter_map(|pos| {
-            let cell = map[pos];
-            if is_unit(cell) {
-                Some(Unit { army: cell, pos: pos, hit_points: 200, attack_power: 3 })
-            } else {
-                None
-            }
-        })
+        .find_markers(|&cell| if is_unit(cell) { Some(cell) } else { None })
+        .into_iter()
+        .map(|(pos, army)| Unit { army, pos, hit_points: 200, attack_power: 3 })
         .collect();
-    State { map: map, units: units, rounds_completed: 0 }
+    let search = GridSearch::new(map.rect());
+    State { map: map, units: units, rounds_completed: 0, search }
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let mut state = parse_input(input);
     let (rounds, remaining_hit_points) = state.run_until_done();
     rounds * remaining_hit_points
@@ -630,7 +612,7 @@ fn upgrade_army(state: &State, army: u8, attack_power: u32) -> State {
     state
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let start_state = parse_input(input);
     let mut elf_attack_power = 3;
     loop {
@@ -30,7 +30,7 @@ impl UnidentifiedInstruction {
     }
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let mut lines = input.lines();
     let mut answer = 0;
     while let Some(line) = lines.next() {
@@ -66,7 +66,7 @@ fn part1example() {
 After:  [3, 2, 2, 1]"), 1);
 }
 
-fn part2(input: &str) -> Value {
+pub fn part2(input: &str) -> Value {
     let mut lines = input.lines();
     let mut blank_lines = 0;
 
@@ -1,4 +1,4 @@
-use aoc::geom::{Matrix, Point, Rect};
+use aoc::geom::{GrowableMatrix, Point, Rect};
 use regex::Regex;
 
 const SAND: u8 = '.' as u8;
@@ -16,31 +16,23 @@ x=498, y=10..13
 x=504, y=10..13
 y=13, x=498..504";
 
-fn parse_input(input: &str) -> Matrix<u8> {
+// Returns a matrix whose rect is exactly the bounding rect of the clay.
+fn parse_input(input: &str) -> GrowableMatrix<u8> {
     let re = Regex::new(r"^(.)=(\d+), (.)=(\d+)\.\.(\d+)$").unwrap();
-    let rects = input.lines()
-        .map(|line| {
-            let captures = re.captures(line).unwrap();
-            let (a_var, a_val, _b_var, b_min, b_max) = (
-                captures.get(1).unwrap().as_str(),
-                captures.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-                captures.get(3).unwrap().as_str(),
-                captures.get(4).unwrap().as_str().parse::<i32>().unwrap(),
-                captures.get(5).unwrap().as_str().parse::<i32>().unwrap());
-            match a_var {
-                "x" => Rect::from_inclusive_ranges(a_val..=a_val, b_min..=b_max),
-                "y" => Rect::from_inclusive_ranges(b_min..=b_max, a_val..=a_val),
-                _ => panic!(),
-            }
-        })
-        .collect::<Vec<Rect>>();
-    let bounding_rect = rects
-        .iter()
-        .fold(Rect::empty(), |acc, rect| Rect::bounding_rects(&acc, rect))
-        // Padding left and right with 1 column is always enough.
-        .padded(1, 1, 0, 0);
-    let mut mat = Matrix::new(&bounding_rect, SAND);
-    for rect in rects {
+    let mut mat = GrowableMatrix::new(&Rect::empty(), SAND);
+    for line in input.lines() {
+        let captures = re.captures(line).unwrap();
+        let (a_var, a_val, _b_var, b_min, b_max) = (
+            captures.get(1).unwrap().as_str(),
+            captures.get(2).unwrap().as_str().parse::<i32>().unwrap(),
+            captures.get(3).unwrap().as_str(),
+            captures.get(4).unwrap().as_str().parse::<i32>().unwrap(),
+            captures.get(5).unwrap().as_str().parse::<i32>().unwrap());
+        let rect = match a_var {
+            "x" => Rect::from_inclusive_ranges(a_val..=a_val, b_min..=b_max),
+            "y" => Rect::from_inclusive_ranges(b_min..=b_max, a_val..=a_val),
+            _ => panic!(),
+        };
         mat.fill_rect(&rect, CLAY);
     }
     mat
@@ -48,26 +40,26 @@ fn parse_input(input: &str) -> Matrix<u8> {
 
 #[test]
 fn test_parse_input() {
-    assert_eq!(parse_input(EXAMPLE).to_string(), "............#.
-.#..#.......#.
-.#..#..#......
-.#..#..#......
-.#.....#......
-.#.....#......
-.#######......
-..............
-..............
-....#.....#...
-....#.....#...
-....#.....#...
-....#######...");
+    assert_eq!(parse_input(EXAMPLE).to_string(), "...........#
+#..#.......#
+#..#..#.....
+#..#..#.....
+#.....#.....
+#.....#.....
+#######.....
+............
+............
+...#.....#..
+...#.....#..
+...#.....#..
+...#######..");
 }
 
 fn is_passable(cell: u8) -> bool {
     cell == SAND || cell == WATER_FLOWING
 }
 
-fn flow_horizontally(start: Point, step: Point, mat: &mut Matrix<u8>) -> (Point, bool) {
+fn flow_horizontally(start: Point, step: Point, mat: &mut GrowableMatrix<u8>) -> (Point, bool) {
     let mut p = start;
     loop {
         if *mat.get(p + step).unwrap_or(&SAND) == CLAY {
@@ -83,7 +75,7 @@ fn flow_horizontally(start: Point, step: Point, mat: &mut Matrix<u8>) -> (Point,
     }
 }
 
-fn flow(p: Point, mat: &mut Matrix<u8>) {
+fn flow(p: Point, mat: &mut GrowableMatrix<u8>) {
     if p.y < mat.rect().y_min() {
         flow(Point::new(p.x, mat.rect().y_min()), mat);
         return;
@@ -117,23 +109,23 @@ fn flow(p: Point, mat: &mut Matrix<u8>) {
 fn test_flow() {
     let mut mat = parse_input(EXAMPLE);
     flow(Point::new(500, 0), &mut mat);
-    let expected = "......|.....#.
-.#..#||||...#.
-.#..#~~#|.....
-.#..#~~#|.....
-.#~~~~~#|.....
-.#~~~~~#|.....
-.#######|.....
-........|.....
-...|||||||||..
-...|#~~~~~#|..
-...|#~~~~~#|..
-...|#~~~~~#|..
-...|#######|..";
+    let expected = ".....|.....#
+#..#||||...#
+#..#~~#|....
+#..#~~#|....
+#~~~~~#|....
+#~~~~~#|....
+#######|....
+.......|....
+..|||||||||.
+..|#~~~~~#|.
+..|#~~~~~#|.
+..|#~~~~~#|.
+..|#######|.";
     assert_eq!(mat.to_string(), expected, "\nLeft:\n{}\n\nRight:\n{}", mat.to_string(), expected);
 }
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let mut mat = parse_input(input);
     flow(Point::new(500, 0), &mut mat);
     mat.coords().filter(|&point| mat[point] == WATER_AT_REST || mat[point] == WATER_FLOWING).count()
@@ -144,7 +136,7 @@ fn part1example() {
     assert_eq!(part1(EXAMPLE), 57);
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     let mut mat = parse_input(input);
     flow(Point::new(500, 0), &mut mat);
     mat.coords().filter(|&point| mat[point] == WATER_AT_REST).count()
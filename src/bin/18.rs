@@ -1,32 +1,28 @@
+use aoc::cycle;
 use aoc::geom::Matrix;
-use crypto::digest::Digest;
-use crypto::sha1::Sha1;
-use std::collections::HashMap;
 
 const OPEN: u8 = '.' as u8;
 const TREES: u8 = '|' as u8;
 const LUMBERYARD: u8 = '#' as u8;
-const NONE: u8 = ' ' as u8;
 
 fn iterate(input: &Matrix<u8>) -> Matrix<u8> {
-    let mut output = Matrix::new(input.rect(), NONE);
-    for p in input.coords() {
-        let mut counts = [0; 128];
-        let center = input[p];
-        for &p in &p.neighbors_diagonal() {
-            counts[*input.get(p).unwrap_or(&NONE) as usize] += 1;
-        }
-        output[p] = match center {
-            OPEN if counts[TREES as usize] >= 3 => TREES,
-            TREES if counts[LUMBERYARD as usize] >= 3 => LUMBERYARD,
-            LUMBERYARD if counts[LUMBERYARD as usize] < 1 || counts[TREES as usize] < 1 => OPEN,
+    // Counts of neighboring trees and lumberyards.
+    let count = |(trees, lumberyards): (u32, u32), &neighbor: &u8| match neighbor {
+        TREES => (trees + 1, lumberyards),
+        LUMBERYARD => (trees, lumberyards + 1),
+        _ => (trees, lumberyards),
+    };
+    input.map_neighborhood((0, 0), count, |&center, (trees, lumberyards)| {
+        match center {
+            OPEN if trees >= 3 => TREES,
+            TREES if lumberyards >= 3 => LUMBERYARD,
+            LUMBERYARD if lumberyards < 1 || trees < 1 => OPEN,
             _ => center
         }
-    }
-    output
+    })
 }
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let mut mat = input.lines().collect::<Matrix<u8>>();
     for _ in 0..10 {
         mat = iterate(&mat);
@@ -48,30 +44,8 @@ fn part1example() {
 ...#.|..|."), 1147);
 }
 
-fn hash(mat: &Matrix<u8>) -> String {
-    let mut hasher = Sha1::new();
-    hasher.input(mat.as_slice());
-    hasher.result_str()
-}
-
-fn part2(input: &str) -> usize {
-    let mut mat = input.lines().collect::<Matrix<u8>>();
-    let mut hashes = HashMap::new();
-    let mut i = 0;
-    while i < 1000000000 {
-        let hash = hash(&mat);
-
-        if hashes.contains_key(&hash) {
-            let step = i - hashes[&hash];
-            i += (1000000000 - i) / step * step;
-            hashes.clear();
-        }
-
-        hashes.insert(hash, i);
-
-        mat = iterate(&mat);
-        i += 1;
-    }
+pub fn part2(input: &str) -> usize {
+    let mat = cycle::fast_forward(input.lines().collect::<Matrix<u8>>(), iterate, 1000000000);
     mat.coords().filter(|&p| mat[p] == TREES).count() * mat.coords().filter(|&p| mat[p] == LUMBERYARD).count()
 }
 
@@ -1,6 +1,6 @@
 use aoc::vm::*;
 
-fn part1(input: &str) -> Value {
+pub fn part1(input: &str) -> Value {
     let program = Program::parse(input);
     let mut state = State::new(6);
     program.execute(&mut state);
@@ -28,7 +28,7 @@ fn test_sum_of_divisors() {
     assert_eq!(sum_of_divisors(974), 1464);
 }
 
-fn part2(_input: &str) -> u64 {
+pub fn part2(_input: &str) -> u64 {
     sum_of_divisors(10551374)
 }
 
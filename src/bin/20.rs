@@ -1,6 +1,5 @@
-use aoc::geom::{Matrix, Point, Rect};
-use std::cmp::{min, max};
-use std::collections::{HashMap, HashSet, VecDeque};
+use aoc::geom::{Matrix, Point, Rect, SparseGrid};
+use aoc::search::{self, SearchResult};
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Default)]
@@ -10,7 +9,7 @@ struct Room {
 
 #[derive(Debug, Default)]
 struct Map {
-    rooms: HashMap<Point, Room>,
+    rooms: SparseGrid<Room>,
 }
 
 impl Map {
@@ -65,7 +64,7 @@ impl Map {
     }
 
     pub fn get_or_create_room_mut(&mut self, pos: Point) -> &mut Room {
-        self.rooms.entry(pos).or_insert(Room::default())
+        self.rooms.get_or_insert_with(pos, Room::default)
     }
 
     pub fn link_rooms(&mut self, a: Point, b: Point) {
@@ -83,25 +82,16 @@ impl Map {
 
 impl Display for Map {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let (x_min, x_max, y_min, y_max) =
-            self.rooms.keys()
-            .fold(
-                (i32::max_value(), i32::min_value(), i32::max_value(), i32::min_value()),
-                |(x_min, x_max, y_min, y_max), p| {
-                    (min(x_min, p.x), max(x_max, p.x), min(y_min, p.y), max(y_max, p.y))
-                }
-            );
+        let rect = self.rooms.rect();
         let mut mat = Matrix::new(
-            &Rect::from_inclusive_ranges(2 * x_min - 1 ..= 2 * x_max + 1, 2 * y_min - 1 ..= 2 * y_max + 1),
+            &Rect::from_inclusive_ranges(2 * rect.x_min() - 1 ..= 2 * rect.x_max() + 1, 2 * rect.y_min() - 1 ..= 2 * rect.y_max() + 1),
             '#' as u8);
-        for (&pos, room) in self.rooms.iter() {
+        for (pos, room) in self.rooms.iter() {
             let mp = 2 * pos;
             mat[mp] = if mp == Point::default() { 'X' as u8 } else { '.' as u8 };
-            if room.neighbors.iter().find(|n| **n == pos + Point::right()).is_some() {
-                mat[mp + Point::right()] = '|' as u8;
-            }
-            if room.neighbors.iter().find(|n| **n == pos + Point::down()).is_some() {
-                mat[mp + Point::down()] = '-' as u8;
+            for &neighbor in &room.neighbors {
+                let step = neighbor - pos;
+                mat[mp + step] = if step.y == 0 { '|' as u8 } else { '-' as u8 };
             }
         }
         write!(f, "{}", mat)
@@ -165,23 +155,13 @@ fn test_map_from_regex() {
 ###############");
 }
 
-fn part1(input: &str) -> usize {
+fn room_distances(map: &Map) -> SearchResult<Point, usize> {
+    search::bfs(Point::default(), |&pos| map.rooms[pos].neighbors.clone(), |_| false)
+}
+
+pub fn part1(input: &str) -> usize {
     let map = Map::from_regex(input.trim());
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back((Point::default(), 0));
-    let mut max_dist = 0;
-    while let Some((pos, dist)) = queue.pop_front() {
-        if visited.contains(&pos) {
-            continue;
-        }
-        visited.insert(pos);
-        max_dist = max(max_dist, dist);
-        for &neighbor in &map.rooms[&pos].neighbors {
-            queue.push_back((neighbor, dist + 1));
-        }
-    }
-    max_dist
+    room_distances(&map).distances().values().cloned().max().unwrap()
 }
 
 #[test]
@@ -193,25 +173,9 @@ fn part1example() {
     assert_eq!(part1("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$"), 31);
 }
 
-fn part2(input: &str) -> usize {
+pub fn part2(input: &str) -> usize {
     let map = Map::from_regex(input.trim());
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back((Point::default(), 0));
-    let mut count = 0;
-    while let Some((pos, dist)) = queue.pop_front() {
-        if visited.contains(&pos) {
-            continue;
-        }
-        visited.insert(pos);
-        if dist >= 1000 {
-            count += 1;
-        }
-        for &neighbor in &map.rooms[&pos].neighbors {
-            queue.push_back((neighbor, dist + 1));
-        }
-    }
-    count
+    room_distances(&map).distances().values().filter(|&&dist| dist >= 1000).count()
 }
 
 fn main() {
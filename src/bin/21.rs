@@ -1,7 +1,7 @@
+use aoc::cycle;
 use aoc::vm::*;
-use std::collections::HashSet;
 
-fn part1(input: &str) -> Value {
+pub fn part1(input: &str) -> Value {
     let prog = Program::parse(input);
     let mut state = State::new(6);
     while state.ip() != prog.instructions().len() - 1 {
@@ -10,41 +10,23 @@ fn part1(input: &str) -> Value {
     state.fetch(1).unwrap()
 }
 
-fn part2(_input: &str) -> Value {
-    let mut seen = HashSet::new();
-    let mut prev = 0;
-    for b in opt_iter() {
-        if seen.contains(&b) {
-            return prev;
-        }
-        prev = b;
-        seen.insert(b);
-    }
-    panic!();
+// The last value of b before the sequence starts repeating.
+pub fn part2(_input: &str) -> Value {
+    let cycle = cycle::brent(0, |&b| next_b(b));
+    (0..cycle.start + cycle.length - 1).fold(0, |b, _| next_b(b))
 }
 
-struct OptIter {
-    b: Value
-}
-
-impl Iterator for OptIter {
-    type Item = Value;
-    fn next(&mut self) -> Option<Value> {
-        let mut c: Value = self.b | 0x10000;
-        self.b = 10605201;
-        loop {
-            self.b = ((self.b + (c & 0xff)) * 65899) & 0xffffff;
-            if c < 0x100 {
-                break;
-            }
-            c /= 256;
+fn next_b(b: Value) -> Value {
+    let mut c: Value = b | 0x10000;
+    let mut b = 10605201;
+    loop {
+        b = ((b + (c & 0xff)) * 65899) & 0xffffff;
+        if c < 0x100 {
+            break;
         }
-        Some(self.b)
+        c /= 256;
     }
-}
-
-fn opt_iter() -> OptIter {
-    OptIter { b: 0 }
+    b
 }
 
 #[cfg(test)]
@@ -75,7 +57,7 @@ impl Iterator for RealIter {
 
 #[test]
 fn test_sequence() {
-    let opt = opt_iter();
+    let opt = std::iter::successors(Some(next_b(0)), |&b| Some(next_b(b)));
     let real = real_iter("#ip 3
 seti 123 0 1
 bani 1 456 1
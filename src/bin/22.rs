@@ -1,6 +1,6 @@
 use aoc::geom::*;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use aoc::search;
+use std::collections::HashMap;
 use regex::Regex;
 
 #[repr(u8)]
@@ -106,32 +106,6 @@ impl Cave {
     }
 }
 
-#[derive(Eq)]
-struct State {
-    pos: Point,
-    tool: Tool,
-    time: u32,
-}
-
-impl PartialEq for State {
-    fn eq(&self, rhs: &State) -> bool {
-        self.time == rhs.time
-    }
-}
-
-impl Ord for State {
-    fn cmp(&self, rhs: &State) -> Ordering {
-        // Greater states are those with lower times.
-        self.time.cmp(&rhs.time).reverse()
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, rhs: &State) -> Option<Ordering> {
-        Some(self.cmp(rhs))
-    }
-}
-
 fn parse_input(input: &str) -> (u32, Point) {
     let captures = Regex::new(r"(?s:^depth:\s*(\d+)\ntarget:\s*(\d+),(\d+)\s*$)").unwrap().captures(input).unwrap();
     (
@@ -143,7 +117,7 @@ fn parse_input(input: &str) -> (u32, Point) {
     )
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let (depth, target) = parse_input(input);
     let mut cave = Cave::new(depth, target);
     Rect::from_inclusive_ranges(0..=target.x, 0..=target.y)
@@ -157,40 +131,30 @@ fn part1example() {
     assert_eq!(part1("depth: 510\ntarget: 10,10\n"), 114);
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let (depth, target) = parse_input(input);
     let mut cave = Cave::new(depth, target);
 
-    let mut visited = HashSet::new();
-    let mut queue = BinaryHeap::new();
-    queue.push(State { pos: Point::origin(), tool: Tool::Torch, time: 0 });
-    while let Some(state) = queue.pop() {
-        let cell = cave.get_cell(state.pos);
-        if cell == Cell::Blocked {
-            continue;
-        }
-        if !cell.can_use(state.tool) {
-            continue;
-        }
-        if state.pos == target && state.tool == Tool::Torch {
-            return state.time;
-        }
-
-        if visited.contains(&(state.pos, state.tool)) {
-            continue;
-        }
-        visited.insert((state.pos, state.tool));
-
-        for neighbor in &state.pos.neighbors() {
-            queue.push(State { pos: *neighbor, tool: state.tool, time: state.time + 1 });
-        }
-        for &next_tool in Tool::all() {
-            if next_tool != state.tool {
-                queue.push(State { pos: state.pos, tool: next_tool, time: state.time + 7 });
+    let result = search::dijkstra(
+        (Point::origin(), Tool::Torch),
+        |&(pos, tool)| {
+            let mut next = Vec::new();
+            for &neighbor in &pos.neighbors() {
+                let cell = cave.get_cell(neighbor);
+                if cell != Cell::Blocked && cell.can_use(tool) {
+                    next.push(((neighbor, tool), 1));
+                }
             }
-        }
-    }
-    panic!();
+            let cell = cave.get_cell(pos);
+            for &next_tool in Tool::all() {
+                if next_tool != tool && cell.can_use(next_tool) {
+                    next.push(((pos, next_tool), 7));
+                }
+            }
+            next
+        },
+        |&state| state == (target, Tool::Torch));
+    result.goal_distance().unwrap()
 }
 
 #[test]
@@ -1,5 +1,5 @@
 use aoc::geom::*;
-use fixedbitset::FixedBitSet;
+use aoc::graph::Graph;
 use regex::Regex;
 
 struct Nanobot {
@@ -34,7 +34,7 @@ fn parse_input(input: &str) -> Vec<Nanobot> {
         .collect()
 }
 
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let nanobots = parse_input(input);
     let strongest = nanobots.iter()
         .max_by_key(|nanobot| nanobot.r)
@@ -58,75 +58,17 @@ pos=<1,3,1>, r=1
 "), 7);
 }
 
-/*
-https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm
-
-BronKerbosch1(Ø, all_vertices, Ø)
-
-BronKerbosch1(R, P, X):
-   if P and X are both empty:
-       report R as a maximal clique
-   for each vertex v in P:
-       BronKerbosch1(R ⋃ {v}, P ⋂ N(v), X ⋂ N(v))
-       P := P \ {v}
-       X := X ⋃ {v}
- */
-fn find_maximal_cliques(adj: &Vec<FixedBitSet>, n: usize, r: FixedBitSet, mut p: FixedBitSet, mut x: FixedBitSet, out: &mut Vec<FixedBitSet>, largest_size: &mut usize) {
-    let p_size = p.count_ones(..);
-    let r_size = r.count_ones(..);
-    let x_size = x.count_ones(..);
-
-    // Optimization: if our current proto-clique plus candidate vertices is smaller than the
-    // largest maximal clique found so far, then we can give up already.
-    if r_size + p_size < *largest_size {
-        return;
-    }
-
-    if p_size == 0 && x_size == 0 {
-        if r_size > *largest_size {
-            *largest_size = r_size;
-            out.clear();
-        }
-        out.push(r);
-    } else {
-        let ones = p.ones().collect::<Vec<usize>>();
-        let mut v = FixedBitSet::with_capacity(n);
-        for i in ones {
-            v.set(i, true);
-            let nv = &adj[i];
-            find_maximal_cliques(adj, n, &r | &v, &p & nv, &x & nv, out, largest_size);
-            p.set(i, false);
-            x.insert(i);
-            v.set(i, false);
-        }
-    }
-}
-
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let nanobots = parse_input(input);
-    let n = nanobots.len();
-
-    // Build adjacency matrix.
-    let adj = (0..n)
-        .map(|i| {
-            let mut neigh = FixedBitSet::with_capacity(n);
-            for j in 0..n {
-                neigh.set(j, i != j && nanobots[i].overlaps_with(&nanobots[j]));
-            }
-            neigh
-        })
-        .collect();
-
-    let mut out = Vec::new();
-    let mut largest_size = 0;
-    find_maximal_cliques(&adj, n, FixedBitSet::with_capacity(n), (0..n).collect(), FixedBitSet::with_capacity(n), &mut out, &mut largest_size);
+    let graph = Graph::from_fn(nanobots.len(), |i, j| nanobots[i].overlaps_with(&nanobots[j]));
 
     let origin = Point3::origin();
-    out.iter()
-        .map(|solution| {
-            solution
-                .ones()
-                .map(|i| nanobots[i].pos.distance_to(&origin).saturating_sub(nanobots[i].r))
+    graph.maximum_cliques()
+        .iter()
+        .map(|clique| {
+            clique
+                .iter()
+                .map(|&i| nanobots[i].pos.distance_to(&origin).saturating_sub(nanobots[i].r))
                 .max()
                 .unwrap()
         })
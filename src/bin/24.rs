@@ -249,7 +249,7 @@ fn fight_until_end(armies: &mut Vec<Army>) -> Option<usize> {
     }
 }
 
-fn part1(input: &str) -> u32 {
+pub fn part1(input: &str) -> u32 {
     let mut armies = parse_input(input);
     let winning_army = fight_until_end(&mut armies).unwrap();
     armies[winning_army].groups.iter().map(|group| group.num_units).sum()
@@ -282,11 +282,10 @@ fn boost_army(armies: &Vec<Army>, name: &str, boost: u32) -> Vec<Army> {
     boosted_armies
 }
 
-fn part2(input: &str) -> u32 {
+pub fn part2(input: &str) -> u32 {
     let armies = parse_input(input);
     let immune_system_name = "Immune System";
     for boost in 0.. {
-        println!("Boost: {}", boost);
         let mut boosted_armies = boost_army(&armies, immune_system_name, boost);
         let winning_army = fight_until_end(&mut boosted_armies);
         if let Some(winning_army) = winning_army {
@@ -1,39 +1,7 @@
-use std::collections::HashSet;
-use std::str::FromStr;
+use aoc::disjoint_set;
+use aoc::geom::PointN;
 
-#[derive(Debug, Default, Clone)]
-struct Point4([i32; 4]);
-
-impl Point4 {
-    fn distance_to(&self, other: &Point4) -> u32 {
-        (self.0[0] - other.0[0]).abs() as u32 +
-            (self.0[1] - other.0[1]).abs() as u32 +
-            (self.0[2] - other.0[2]).abs() as u32 +
-            (self.0[3] - other.0[3]).abs() as u32
-    }
-}
-
-#[derive(Debug)]
-struct ParsePoint4Error();
-
-impl FromStr for Point4 {
-    type Err = ParsePoint4Error;
-    fn from_str(s: &str) -> Result<Point4, ParsePoint4Error> {
-        let mut parts = s.split(",");
-        let mut p = Point4::default();
-        for i in 0..4 {
-            if let Some(c) = parts.next() {
-                p.0[i] = c.parse::<i32>().map_err(|_| ParsePoint4Error())?;
-            } else {
-                return Err(ParsePoint4Error());
-            }
-        }
-        if parts.next().is_some() {
-            return Err(ParsePoint4Error());
-        }
-        Ok(p)
-    }
-}
+type Point4 = PointN<4>;
 
 fn parse_input(input: &str) -> Vec<Point4> {
     input.lines()
@@ -43,44 +11,9 @@ fn parse_input(input: &str) -> Vec<Point4> {
         .collect()
 }
 
-fn connections(points: &Vec<Point4>) -> Vec<Vec<usize>> {
-    let n = points.len();
-    (0..n)
-        .map(|i| {
-            (0..n)
-                .filter_map(|j| {
-                    if i != j && points[i].distance_to(&points[j]) <= 3 {
-                        Some(j)
-                    } else {
-                        None
-                    }
-                })
-                .collect()
-        })
-        .collect()
-}
-
-fn flood_fill(connections: &Vec<Vec<usize>>, start: usize, remaining: &mut HashSet<usize>) {
-    if !remaining.contains(&start) {
-        return;
-    }
-    remaining.remove(&start);
-    for &n in &connections[start] {
-        flood_fill(connections, n, remaining);
-    }
-}
-
-fn part1(input: &str) -> usize {
+pub fn part1(input: &str) -> usize {
     let points = parse_input(input);
-    let connections = connections(&points);
-    let n = points.len();
-    let mut remaining: HashSet<usize> = (0..n).collect();
-    let mut num_constellations = 0;
-    while let Some(start) = remaining.iter().next() {
-        flood_fill(&connections, *start, &mut remaining);
-        num_constellations += 1;
-    }
-    num_constellations
+    disjoint_set::cluster_points(&points, 3).num_sets()
 }
 
 #[test]
@@ -125,7 +58,7 @@ fn part1examples() {
 -1,-2,0,-2"), 8);
 }
 
-fn part2(_input: &str) -> String {
+pub fn part2(_input: &str) -> String {
     "TODO".to_string()
 }
 
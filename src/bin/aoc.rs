@@ -0,0 +1,69 @@
+// Runs any selection of days from a single binary, e.g.:
+//
+//     cargo run --bin aoc -- --day 15 --part 2
+//     cargo run --bin aoc -- --day 1-10
+//     cargo run --bin aoc -- --all
+//
+// Each day's source file is compiled in as a module; its own `main` is unused here.
+
+use aoc::Solution;
+
+// Days marked (fallible) have parts that return a Result.
+macro_rules! days {
+    ($($day:literal => $module:ident in $path:literal $(($fallible:ident))?,)*) => {
+        $(
+            #[allow(dead_code)]
+            #[path = $path]
+            mod $module;
+        )*
+
+        fn solutions() -> Vec<Solution> {
+            vec![$(solution!($day, $module $(, $fallible)?),)*]
+        }
+    }
+}
+
+macro_rules! solution {
+    ($day:literal, $module:ident) => {
+        Solution::new(2018, $day, $module::part1, $module::part2)
+    };
+    ($day:literal, $module:ident, fallible) => {
+        Solution::try_new(2018, $day, $module::part1, $module::part2)
+    };
+}
+
+days! {
+    1 => day01 in "01.rs" (fallible),
+    2 => day02 in "02.rs",
+    3 => day03 in "03.rs" (fallible),
+    4 => day04 in "04.rs",
+    5 => day05 in "05.rs",
+    6 => day06 in "06.rs",
+    7 => day07 in "07.rs",
+    8 => day08 in "08.rs",
+    9 => day09 in "09.rs",
+    10 => day10 in "10.rs",
+    11 => day11 in "11.rs",
+    12 => day12 in "12.rs",
+    13 => day13 in "13.rs",
+    14 => day14 in "14.rs",
+    15 => day15 in "15.rs",
+    16 => day16 in "16.rs",
+    17 => day17 in "17.rs",
+    18 => day18 in "18.rs",
+    19 => day19 in "19.rs",
+    20 => day20 in "20.rs",
+    21 => day21 in "21.rs",
+    22 => day22 in "22.rs",
+    23 => day23 in "23.rs",
+    24 => day24 in "24.rs",
+    25 => day25 in "25.rs",
+}
+
+fn main() {
+    let options = aoc::parse_options();
+    if options.days.is_none() && !options.all {
+        aoc::exit_with_usage("Select days to run with --day or --all");
+    }
+    aoc::run_solutions(&solutions(), &options);
+}
@@ -0,0 +1,57 @@
+use aoc::input::{self, InputSource};
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+Fetches a puzzle input into the input cache. Defaults to today's puzzle.
+
+Options:
+    --year YYYY   fetch the input for the given year
+    --day N       fetch the input for the given day
+    --force       overwrite the input if it is already cached";
+
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse_value(name: &str, value: Option<String>) -> u32 {
+    value
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or_else(|| exit_with_usage(&format!("{} requires a number", name)))
+}
+
+fn main() {
+    let mut year = None;
+    let mut day = None;
+    let mut force = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--year" => year = Some(parse_value("--year", args.next())),
+            "--day" => day = Some(parse_value("--day", args.next())),
+            "--force" => force = true,
+            _ => exit_with_usage(&format!("unknown argument {:?}", arg)),
+        }
+    }
+    let today = input::puzzle_today();
+    let year = year.or(today.map(|(year, _)| year))
+        .unwrap_or_else(|| exit_with_usage("No puzzle was released today; pass --year"));
+    let day = day.or(today.map(|(_, day)| day))
+        .unwrap_or_else(|| exit_with_usage("No puzzle was released today; pass --day"));
+
+    let source = InputSource::default();
+    if let Some(cached) = source.cached_input_file_name(year, day) {
+        if !force {
+            eprintln!("Input file {} already exists; pass --force to overwrite it", cached.display());
+            process::exit(1);
+        }
+    }
+    match source.fetch_and_cache(year, day) {
+        Ok(_) => println!("Fetched input for {} day {} into {}", year, day, source.input_file_name(year, day).display()),
+        Err(err) => {
+            eprintln!("Input for {} day {} could not be fetched: {}", year, day, err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// A cycle in the sequence x0, x1 = step(x0), x2 = step(x1), ...: start is the index of the first
+// state that recurs, and length the number of steps after which it recurs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The smallest index whose state is equal to the state at index n.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start { n } else { self.start + (n - self.start) % self.length }
+    }
+}
+
+// Floyd's tortoise and hare. Needs only equality and constant memory, but computes about three
+// times as many steps as there are states before the first repeat. Loops forever if the sequence
+// never repeats.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+    where S: Clone + Eq, F: FnMut(&S) -> S
+{
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    let mut cycle_start = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start: cycle_start, length }
+}
+
+// Brent's algorithm. Like floyd, but typically needs fewer steps.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+    where S: Clone + Eq, F: FnMut(&S) -> S
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut cycle_start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+    Cycle { start: cycle_start, length }
+}
+
+// Finds the cycle by remembering every state, so each state is computed only once.
+pub fn find_cycle<S, F>(start: S, step: F) -> Cycle
+    where S: Clone + Hash + Eq, F: FnMut(&S) -> S
+{
+    find_cycle_by_key(start, step, S::clone)
+}
+
+// Like find_cycle, but states are considered equal if their keys are.
+pub fn find_cycle_by_key<S, K, F, G>(start: S, mut step: F, mut key: G) -> Cycle
+    where K: Hash + Eq, F: FnMut(&S) -> S, G: FnMut(&S) -> K
+{
+    let mut seen = HashMap::new();
+    let mut state = start;
+    for i in 0.. {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => return Cycle { start: *entry.get(), length: i - entry.get() },
+            Entry::Vacant(entry) => { entry.insert(i); }
+        }
+        state = step(&state);
+    }
+    unreachable!()
+}
+
+// Returns the state after n steps, skipping over whole cycles once one is found.
+pub fn fast_forward<S, F>(start: S, step: F, n: usize) -> S
+    where S: Clone + Hash + Eq, F: FnMut(&S) -> S
+{
+    fast_forward_by_key(start, step, n, S::clone)
+}
+
+// Like fast_forward, but states are considered equal if their keys are.
+pub fn fast_forward_by_key<S, K, F, G>(start: S, step: F, n: usize, mut key: G) -> S
+    where K: Hash + Eq, F: FnMut(&S) -> S, G: FnMut(&S) -> K
+{
+    fast_forward_translated(start, step, n, |state| (key(state), 0), |state, _| state)
+}
+
+// Like fast_forward_by_key, for states that repeat up to a translation. The key function returns
+// the state's shape, which must not depend on its position, together with its position. Once a
+// shape recurs, each further pass through the cycle moves the state by the same delta, so the
+// result is the equivalent state within the first cycle, passed to translate with the total delta.
+pub fn fast_forward_translated<S, K, F, G, T>(start: S, mut step: F, n: usize, mut key: G, translate: T) -> S
+    where K: Hash + Eq, F: FnMut(&S) -> S, G: FnMut(&S) -> (K, i64), T: FnOnce(S, i64) -> S
+{
+    let mut history = vec![];
+    let mut seen = HashMap::new();
+    let mut state = start;
+    for i in 0..n {
+        let (shape, position) = key(&state);
+        match seen.entry(shape) {
+            Entry::Occupied(entry) => {
+                let (cycle_start, cycle_position) = *entry.get();
+                let cycle = Cycle { start: cycle_start, length: i - cycle_start };
+                let passes = ((n - cycle.start) / cycle.length) as i64;
+                let state = history.swap_remove(cycle.reduce(n));
+                return translate(state, passes * (position - cycle_position));
+            }
+            Entry::Vacant(entry) => { entry.insert((i, position)); }
+        }
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+#[cfg(test)]
+fn square_plus_one(&x: &u32) -> u32 {
+    (x * x + 1) % 1000
+}
+
+#[test]
+fn test_find_cycle() {
+    // 0 1 2 5 26 677 330 901 802 205 26 ...
+    let expected = Cycle { start: 4, length: 6 };
+    assert_eq!(floyd(0, square_plus_one), expected);
+    assert_eq!(brent(0, square_plus_one), expected);
+    assert_eq!(find_cycle(0, square_plus_one), expected);
+    assert_eq!(find_cycle_by_key(0, square_plus_one, |&x| x % 1000), expected);
+    assert_eq!(find_cycle(26, square_plus_one), Cycle { start: 0, length: 6 });
+    assert_eq!(brent(7, |&x| x), Cycle { start: 0, length: 1 });
+}
+
+#[test]
+fn test_cycle_reduce() {
+    let cycle = Cycle { start: 4, length: 6 };
+    assert_eq!(cycle.reduce(3), 3);
+    assert_eq!(cycle.reduce(9), 9);
+    assert_eq!(cycle.reduce(10), 4);
+    assert_eq!(cycle.reduce(1_000_000_003), 7);
+}
+
+#[test]
+fn test_fast_forward() {
+    assert_eq!(fast_forward(0, square_plus_one, 3), 5);
+    assert_eq!(fast_forward(0, square_plus_one, 10), 26);
+    assert_eq!(fast_forward(0, square_plus_one, 1_000_000_003), 901);
+    assert_eq!(fast_forward_by_key(0u64, |&x| x + 1, 1_000_000_003, |&x| x % 3), 1);
+}
+
+#[test]
+fn test_fast_forward_translated() {
+    // A glider on a line: the pattern alternates between two shapes, advancing 3 every 2 steps.
+    let step = |&(shape, position): &(u8, i64)| if shape == 0 { (1, position + 1) } else { (0, position + 2) };
+    let key = |&(shape, position): &(u8, i64)| (shape, position);
+    let translate = |(shape, position), delta| (shape, position + delta);
+    assert_eq!(fast_forward_translated((0, 10), step, 0, key, translate), (0, 10));
+    assert_eq!(fast_forward_translated((0, 10), step, 5, key, translate), (1, 17));
+    assert_eq!(fast_forward_translated((0, 10), step, 1_000_000_001, key, translate), (1, 1_500_000_011));
+}
@@ -0,0 +1,159 @@
+use crate::geom::PointN;
+use std::collections::HashMap;
+use std::iter;
+
+// A partition of the items 0..n into disjoint sets, which can be merged. Uses union by rank and
+// path compression, so operations take nearly constant amortized time.
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    num_sets: usize,
+}
+
+impl DisjointSet {
+    // Creates n singleton sets.
+    pub fn new(n: usize) -> DisjointSet {
+        DisjointSet { parent: (0..n).collect(), rank: vec![0; n], num_sets: n }
+    }
+
+    // The number of items.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn num_sets(&self) -> usize {
+        self.num_sets
+    }
+
+    // The representative of the set containing item. Two items are in the same set if and only if
+    // they have the same representative.
+    pub fn find(&mut self, item: usize) -> usize {
+        let mut root = item;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut item = item;
+        while self.parent[item] != root {
+            let next = self.parent[item];
+            self.parent[item] = root;
+            item = next;
+        }
+        root
+    }
+
+    // Merges the sets containing a and b. Returns false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (child, root) = if self.rank[a] < self.rank[b] { (a, b) } else { (b, a) };
+        self.parent[child] = root;
+        if self.rank[child] == self.rank[root] {
+            self.rank[root] += 1;
+        }
+        self.num_sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // All sets, each sorted, ordered by their smallest item.
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut indices = HashMap::new();
+        let mut sets: Vec<Vec<usize>> = vec![];
+        for item in 0..self.len() {
+            let root = self.find(item);
+            let index = *indices.entry(root).or_insert_with(|| {
+                sets.push(vec![]);
+                sets.len() - 1
+            });
+            sets[index].push(item);
+        }
+        sets
+    }
+}
+
+// Puts items in the same set if they are transitively connected by pairs at most threshold apart.
+// Compares every pair of items; see cluster_points for a faster version for points.
+pub fn cluster<T, D, F>(items: &[T], threshold: D, mut distance: F) -> DisjointSet
+    where D: PartialOrd, F: FnMut(&T, &T) -> D
+{
+    let mut sets = DisjointSet::new(items.len());
+    for (i, a) in items.iter().enumerate() {
+        for (j, b) in items.iter().enumerate().skip(i + 1) {
+            if distance(a, b) <= threshold {
+                sets.union(i, j);
+            }
+        }
+    }
+    sets
+}
+
+// Like cluster with the Manhattan distance, but only compares points in nearby buckets of size
+// threshold, so it takes roughly linear time if the points are spread out.
+pub fn cluster_points<const N: usize>(points: &[PointN<N>], threshold: u32) -> DisjointSet {
+    let size = threshold.max(1) as i32;
+    let bucket_of = |point: &PointN<N>| {
+        let mut bucket = *point;
+        for coord in bucket.0.iter_mut() {
+            *coord = coord.div_euclid(size);
+        }
+        bucket
+    };
+
+    let mut sets = DisjointSet::new(points.len());
+    let mut buckets: HashMap<PointN<N>, Vec<usize>> = HashMap::new();
+    for (i, &point) in points.iter().enumerate() {
+        let bucket = bucket_of(&point);
+        // Points within the threshold differ by at most one bucket along each axis.
+        for neighbor in iter::once(bucket).chain(bucket.neighbors_diagonal()) {
+            for &j in buckets.get(&neighbor).into_iter().flatten() {
+                if point.manhattan_distance(points[j]) <= threshold {
+                    sets.union(i, j);
+                }
+            }
+        }
+        buckets.entry(bucket).or_default().push(i);
+    }
+    sets
+}
+
+#[test]
+fn test_disjoint_set() {
+    let mut sets = DisjointSet::new(6);
+    assert_eq!((sets.len(), sets.num_sets()), (6, 6));
+    assert!(sets.union(0, 3));
+    assert!(sets.union(4, 3));
+    assert!(sets.union(1, 5));
+    assert!(!sets.union(0, 4));
+    assert!(sets.same_set(4, 0));
+    assert!(!sets.same_set(1, 0));
+    assert_eq!(sets.num_sets(), 3);
+    assert_eq!(sets.sets(), vec![vec![0, 3, 4], vec![1, 5], vec![2]]);
+    assert!(DisjointSet::new(0).is_empty());
+}
+
+#[test]
+fn test_cluster() {
+    let items = [1, 10, 3, 12, 20, 5];
+    assert_eq!(cluster(&items, 2, |&a: &i32, &b: &i32| (a - b).abs()).sets(), vec![vec![0, 2, 5], vec![1, 3], vec![4]]);
+}
+
+#[test]
+fn test_cluster_points() {
+    let points: Vec<PointN<2>> = ["0,0", "3,0", "9,0", "6,0", "-2,-2", "-3,-3", "20,20", "-4,-4"].iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    let expected = cluster(&points, 3, |&a, &b| a.manhattan_distance(b)).sets();
+    assert_eq!(expected, vec![vec![0, 1, 2, 3], vec![4, 5, 7], vec![6]]);
+    assert_eq!(cluster_points(&points, 3).sets(), expected);
+    assert_eq!(cluster_points(&points, 0).num_sets(), points.len());
+}
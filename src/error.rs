@@ -0,0 +1,66 @@
+use std::fmt::{Display, Formatter};
+
+// An error from parsing the input or solving the puzzle. If the error can be traced back to a
+// particular line of the input, that line is included so it can be reported to the user.
+//
+// This deliberately does not implement std::error::Error, so that any type that does can be
+// converted into it with the `?` operator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+    line: Option<(usize, String)>,
+}
+
+impl Error {
+    pub fn new<S: Into<String>>(message: S) -> Error {
+        Error { message: message.into(), line: None }
+    }
+
+    // Attaches the (1-based) line number and contents of the offending input line.
+    pub fn at_line(self, line_number: usize, line: &str) -> Error {
+        Error { line: Some((line_number, line.to_string())), ..self }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line_number(&self) -> Option<usize> {
+        self.line.as_ref().map(|(line_number, _)| *line_number)
+    }
+}
+
+impl<E: std::error::Error> From<E> for Error {
+    fn from(err: E) -> Error {
+        Error::new(err.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match &self.line {
+            Some((line_number, line)) => write!(f, "input line {} ({:?}): {}", line_number, line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// Parses each line of the input with the given function. If any line fails to parse, the error
+// is annotated with that line.
+pub fn parse_lines<T, E, F>(input: &str, mut parse_line: F) -> Result<Vec<T>, Error>
+    where F: FnMut(&str) -> Result<T, E>, E: Into<Error>
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_line(line).map_err(|err| err.into().at_line(index + 1, line)))
+        .collect()
+}
+
+#[test]
+fn test_parse_lines() {
+    assert_eq!(parse_lines("1\n2\n3", str::parse::<i32>), Ok(vec![1, 2, 3]));
+    let err = parse_lines("1\nx\n3", str::parse::<i32>).unwrap_err();
+    assert_eq!(err.line_number(), Some(2));
+    assert_eq!(err.to_string(), "input line 2 (\"x\"): invalid digit found in string");
+}
@@ -1,8 +1,12 @@
 use std::cmp;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::fmt::{Display, Formatter};
 use std::iter::FromIterator;
+use std::num::ParseIntError;
 use std::ops;
 use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
 
 // A discrete point on a 2D integer grid.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -23,6 +27,7 @@ impl Point {
     pub fn up() -> Point { Point::new(0, -1) }
     pub fn down() -> Point { Point::new(0, 1) }
 
+    // In reading order, like PointN::neighbors.
     pub fn neighbors(self) -> [Point; 4] {
         [self + Point::up(), self + Point::left(), self + Point::right(), self + Point::down()]
     }
@@ -41,35 +46,30 @@ impl Point {
     }
 
     pub fn distance_to(self, other: Point) -> u32 {
-        (self.x - other.x).abs() as u32 + (self.y - other.y).abs() as u32
+        PointN::from(self).manhattan_distance(other.into())
     }
-}
 
-impl ops::Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Point {
-        Point { x: self.x + rhs.x, y: self.y + rhs.y }
+    pub fn chebyshev_distance(self, other: Point) -> u32 {
+        PointN::from(self).chebyshev_distance(other.into())
     }
-}
 
-impl ops::AddAssign<Point> for Point {
-    fn add_assign(&mut self, rhs: Point) {
-        self.x += rhs.x;
-        self.y += rhs.y;
+    // Rotations are about the origin, with y pointing down, so rotating up clockwise gives right.
+    pub fn rotate_cw(self) -> Point {
+        Point::new(-self.y, self.x)
     }
-}
 
-impl ops::Mul<Point> for i32 {
-    type Output = Point;
-    fn mul(self, rhs: Point) -> Point {
-        Point { x: self * rhs.x, y: self * rhs.y }
+    pub fn rotate_ccw(self) -> Point {
+        Point::new(self.y, -self.x)
     }
-}
 
-impl ops::Mul<i32> for Point {
-    type Output = Point;
-    fn mul(self, rhs: i32) -> Point {
-        Point { x: self.x * rhs, y: self.y * rhs }
+    // Mirrors in the y axis, swapping left and right.
+    pub fn reflect_x(self) -> Point {
+        Point::new(-self.x, self.y)
+    }
+
+    // Mirrors in the x axis, swapping up and down.
+    pub fn reflect_y(self) -> Point {
+        Point::new(self.x, -self.y)
     }
 }
 
@@ -92,6 +92,13 @@ impl Display for Point {
     }
 }
 
+impl FromStr for Point {
+    type Err = ParsePointError;
+    fn from_str(s: &str) -> Result<Point, ParsePointError> {
+        s.parse::<PointN<2>>().map(From::from)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Point3 {
     pub x: i32,
@@ -109,7 +116,19 @@ impl Point3 {
     }
 
     pub fn distance_to(&self, other: &Point3) -> u32 {
-        (self.x - other.x).abs() as u32 + (self.y - other.y).abs() as u32 + (self.z - other.z).abs() as u32
+        PointN::from(*self).manhattan_distance((*other).into())
+    }
+
+    pub fn chebyshev_distance(&self, other: &Point3) -> u32 {
+        PointN::from(*self).chebyshev_distance((*other).into())
+    }
+
+}
+
+impl FromStr for Point3 {
+    type Err = ParsePointError;
+    fn from_str(s: &str) -> Result<Point3, ParsePointError> {
+        s.parse::<PointN<3>>().map(From::from)
     }
 }
 
@@ -119,7 +138,433 @@ impl Display for Point3 {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+// A discrete point on an N-dimensional integer grid. Point and Point3 have named fields for
+// convenience, but convert to and from PointN<2> and PointN<3>, which implement their arithmetic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointN<const N: usize>(pub [i32; N]);
+
+impl<const N: usize> PointN<N> {
+    pub fn new(coords: [i32; N]) -> PointN<N> {
+        PointN(coords)
+    }
+
+    pub fn origin() -> PointN<N> {
+        PointN([0; N])
+    }
+
+    pub fn coords(&self) -> &[i32; N] {
+        &self.0
+    }
+
+    pub fn manhattan_distance(self, other: PointN<N>) -> u32 {
+        self.0.iter().zip(other.0.iter()).map(|(a, b)| (a - b).unsigned_abs()).sum()
+    }
+
+    pub fn chebyshev_distance(self, other: PointN<N>) -> u32 {
+        self.0.iter().zip(other.0.iter()).map(|(a, b)| (a - b).unsigned_abs()).max().unwrap_or(0)
+    }
+
+    // Component-wise minimum. For Point and Point3, this is not Ord::min, which compares in
+    // reading order.
+    pub fn component_min(self, other: PointN<N>) -> PointN<N> {
+        self.zip_with(other, cmp::min)
+    }
+
+    pub fn component_max(self, other: PointN<N>) -> PointN<N> {
+        self.zip_with(other, cmp::max)
+    }
+
+    pub fn abs(self) -> PointN<N> {
+        PointN(self.0.map(i32::abs))
+    }
+
+    pub fn signum(self) -> PointN<N> {
+        PointN(self.0.map(i32::signum))
+    }
+
+    fn zip_with<F: Fn(i32, i32) -> i32>(mut self, other: PointN<N>, f: F) -> PointN<N> {
+        for (a, &b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a = f(*a, b);
+        }
+        self
+    }
+
+    // The 2N points at Manhattan distance 1, in reading order: the last axis varies slowest, so
+    // for 2D points that is up, left, right, down, the same as Point::neighbors.
+    pub fn neighbors(self) -> impl Iterator<Item = PointN<N>> {
+        (0..2 * N).map(move |i| {
+            let mut p = self;
+            if i < N {
+                p.0[N - 1 - i] -= 1;
+            } else {
+                p.0[i - N] += 1;
+            }
+            p
+        })
+    }
+
+    // The 3^N - 1 points at Chebyshev distance 1.
+    pub fn neighbors_diagonal(self) -> impl Iterator<Item = PointN<N>> {
+        (0..3usize.pow(N as u32))
+            .map(move |index| {
+                let mut p = self;
+                let mut rest = index;
+                for coord in p.0.iter_mut() {
+                    *coord += (rest % 3) as i32 - 1;
+                    rest /= 3;
+                }
+                p
+            })
+            .filter(move |&p| p != self)
+    }
+}
+
+impl<const N: usize> Default for PointN<N> {
+    fn default() -> PointN<N> {
+        PointN::origin()
+    }
+}
+
+impl<const N: usize> ops::Index<usize> for PointN<N> {
+    type Output = i32;
+    fn index(&self, axis: usize) -> &i32 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> ops::IndexMut<usize> for PointN<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i32 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> ops::Add<PointN<N>> for PointN<N> {
+    type Output = PointN<N>;
+    fn add(mut self, rhs: PointN<N>) -> PointN<N> {
+        self += rhs;
+        self
+    }
+}
+
+impl<const N: usize> ops::AddAssign<PointN<N>> for PointN<N> {
+    fn add_assign(&mut self, rhs: PointN<N>) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *a += b;
+        }
+    }
+}
+
+impl<const N: usize> ops::Sub<PointN<N>> for PointN<N> {
+    type Output = PointN<N>;
+    fn sub(mut self, rhs: PointN<N>) -> PointN<N> {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const N: usize> ops::SubAssign<PointN<N>> for PointN<N> {
+    fn sub_assign(&mut self, rhs: PointN<N>) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *a -= b;
+        }
+    }
+}
+
+impl<const N: usize> ops::Neg for PointN<N> {
+    type Output = PointN<N>;
+    fn neg(self) -> PointN<N> {
+        PointN(self.0.map(|a| -a))
+    }
+}
+
+impl<const N: usize> ops::Mul<i32> for PointN<N> {
+    type Output = PointN<N>;
+    fn mul(self, rhs: i32) -> PointN<N> {
+        PointN(self.0.map(|a| a * rhs))
+    }
+}
+
+impl<const N: usize> ops::Mul<PointN<N>> for i32 {
+    type Output = PointN<N>;
+    fn mul(self, rhs: PointN<N>) -> PointN<N> {
+        rhs * self
+    }
+}
+
+// Divides each coordinate, rounding towards zero like integer division does.
+impl<const N: usize> ops::Div<i32> for PointN<N> {
+    type Output = PointN<N>;
+    fn div(self, rhs: i32) -> PointN<N> {
+        PointN(self.0.map(|a| a / rhs))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsePointError {
+    WrongDimension { expected: usize, actual: usize },
+    InvalidCoordinate(ParseIntError),
+}
+
+impl Display for ParsePointError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ParsePointError::WrongDimension { expected, actual } => write!(f, "expected {} coordinates, got {}", expected, actual),
+            ParsePointError::InvalidCoordinate(err) => write!(f, "invalid coordinate: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ParsePointError {}
+
+// Parses comma-separated coordinates, like "1,-2,3". Whitespace around coordinates is ignored.
+impl<const N: usize> FromStr for PointN<N> {
+    type Err = ParsePointError;
+    fn from_str(s: &str) -> Result<PointN<N>, ParsePointError> {
+        let parts: Vec<&str> = s.split(',').collect();
+        if parts.len() != N {
+            return Err(ParsePointError::WrongDimension { expected: N, actual: parts.len() });
+        }
+        let mut p = PointN::origin();
+        for (coord, part) in p.0.iter_mut().zip(parts) {
+            *coord = part.trim().parse().map_err(ParsePointError::InvalidCoordinate)?;
+        }
+        Ok(p)
+    }
+}
+
+impl<const N: usize> Display for PointN<N> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        for (i, coord) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", coord)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(p: Point) -> PointN<2> {
+        PointN([p.x, p.y])
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(p: PointN<2>) -> Point {
+        Point::new(p.0[0], p.0[1])
+    }
+}
+
+impl From<Point3> for PointN<3> {
+    fn from(p: Point3) -> PointN<3> {
+        PointN([p.x, p.y, p.z])
+    }
+}
+
+impl From<PointN<3>> for Point3 {
+    fn from(p: PointN<3>) -> Point3 {
+        Point3::new(p.0[0], p.0[1], p.0[2])
+    }
+}
+
+// Implements the arithmetic of a point type with named fields by converting it to PointN, so that
+// it is only written once. The conversions compile away, but only if the functions are inlined
+// into the calling crate.
+macro_rules! impl_point_ops {
+    ($point:ident, $n:expr) => {
+        impl $point {
+            // Component-wise minimum. Not Ord::min, which compares in reading order.
+            #[inline]
+            pub fn component_min(self, other: $point) -> $point {
+                PointN::<$n>::from(self).component_min(other.into()).into()
+            }
+
+            #[inline]
+            pub fn component_max(self, other: $point) -> $point {
+                PointN::<$n>::from(self).component_max(other.into()).into()
+            }
+
+            #[inline]
+            pub fn abs(self) -> $point {
+                PointN::<$n>::from(self).abs().into()
+            }
+
+            #[inline]
+            pub fn signum(self) -> $point {
+                PointN::<$n>::from(self).signum().into()
+            }
+        }
+
+        impl ops::Add<$point> for $point {
+            type Output = $point;
+            #[inline]
+            fn add(self, rhs: $point) -> $point {
+                (PointN::<$n>::from(self) + rhs.into()).into()
+            }
+        }
+
+        impl ops::AddAssign<$point> for $point {
+            #[inline]
+            fn add_assign(&mut self, rhs: $point) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl ops::Sub<$point> for $point {
+            type Output = $point;
+            #[inline]
+            fn sub(self, rhs: $point) -> $point {
+                (PointN::<$n>::from(self) - rhs.into()).into()
+            }
+        }
+
+        impl ops::SubAssign<$point> for $point {
+            #[inline]
+            fn sub_assign(&mut self, rhs: $point) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl ops::Neg for $point {
+            type Output = $point;
+            #[inline]
+            fn neg(self) -> $point {
+                (-PointN::<$n>::from(self)).into()
+            }
+        }
+
+        impl ops::Mul<i32> for $point {
+            type Output = $point;
+            #[inline]
+            fn mul(self, rhs: i32) -> $point {
+                (PointN::<$n>::from(self) * rhs).into()
+            }
+        }
+
+        impl ops::Mul<$point> for i32 {
+            type Output = $point;
+            #[inline]
+            fn mul(self, rhs: $point) -> $point {
+                rhs * self
+            }
+        }
+
+        // Divides each coordinate, rounding towards zero like integer division does.
+        impl ops::Div<i32> for $point {
+            type Output = $point;
+            #[inline]
+            fn div(self, rhs: i32) -> $point {
+                (PointN::<$n>::from(self) / rhs).into()
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point, 2);
+impl_point_ops!(Point3, 3);
+
+#[test]
+fn point_n_distance_test() {
+    let a = PointN::new([1, -2, 3, 0]);
+    let b = PointN::new([-1, 2, 3, 1]);
+    assert_eq!(a.manhattan_distance(b), 7);
+    assert_eq!(a.chebyshev_distance(b), 4);
+    assert_eq!(a.manhattan_distance(a), 0);
+}
+
+#[test]
+fn point_n_ops_test() {
+    let a = PointN::new([1, 2, 3]);
+    let b = PointN::new([4, 5, 6]);
+    assert_eq!(a + b, PointN::new([5, 7, 9]));
+    assert_eq!(b - a, PointN::new([3, 3, 3]));
+    assert_eq!(-a, PointN::new([-1, -2, -3]));
+    assert_eq!(a * 2, PointN::new([2, 4, 6]));
+    assert_eq!(2 * a, PointN::new([2, 4, 6]));
+    let mut c = a;
+    c += b;
+    c -= a;
+    assert_eq!(c, b);
+    assert_eq!(c[1], 5);
+}
+
+#[test]
+fn point_n_neighbors_test() {
+    let p = PointN::new([0, 0]);
+    assert_eq!(p.neighbors().collect::<Vec<_>>(), vec![
+        PointN::new([0, -1]), PointN::new([-1, 0]), PointN::new([1, 0]), PointN::new([0, 1]),
+    ]);
+    assert!(p.neighbors().map(Point::from).eq(Point::origin().neighbors().iter().cloned()));
+    assert_eq!(p.neighbors_diagonal().count(), 8);
+    assert!(p.neighbors_diagonal().all(|n| n.chebyshev_distance(p) == 1));
+    assert_eq!(PointN::new([5, 5, 5, 5]).neighbors().count(), 8);
+    assert_eq!(PointN::new([5, 5, 5, 5]).neighbors_diagonal().count(), 80);
+}
+
+#[test]
+fn point_n_parse_test() {
+    assert_eq!(" 0,-3,12, 1".parse::<PointN<4>>(), Ok(PointN::new([0, -3, 12, 1])));
+    assert_eq!("1,2".parse::<PointN<3>>(), Err(ParsePointError::WrongDimension { expected: 3, actual: 2 }));
+    assert!("1,x".parse::<PointN<2>>().is_err());
+    assert_eq!(PointN::new([0, -3, 12]).to_string(), "0,-3,12");
+}
+
+#[test]
+fn point_conversion_test() {
+    assert_eq!(PointN::from(Point::new(1, 2)), PointN::new([1, 2]));
+    assert_eq!(Point::from(PointN::new([1, 2])), Point::new(1, 2));
+    assert_eq!(Point3::from(PointN::new([1, 2, 3])), Point3::new(1, 2, 3));
+    assert_eq!("3, 4".parse::<Point>(), Ok(Point::new(3, 4)));
+    assert_eq!("3,4,5".parse::<Point3>(), Ok(Point3::new(3, 4, 5)));
+    assert_eq!(Point::new(1, 1).chebyshev_distance(Point::new(3, -1)), 2);
+    assert_eq!(Point3::new(1, 1, 1).chebyshev_distance(&Point3::new(3, -1, 4)), 3);
+}
+
+#[test]
+fn point_arithmetic_test() {
+    let a = Point::new(3, -4);
+    let b = Point::new(1, 2);
+    assert_eq!(a - b, Point::new(2, -6));
+    assert_eq!(-a, Point::new(-3, 4));
+    assert_eq!(a / 2, Point::new(1, -2));
+    let mut c = a;
+    c -= b;
+    assert_eq!(c, Point::new(2, -6));
+    assert_eq!(a.component_min(b), Point::new(1, -4));
+    assert_eq!(a.component_max(b), Point::new(3, 2));
+    // Ord::min still compares in reading order.
+    assert_eq!(a.min(b), a);
+    assert_eq!(a.abs(), Point::new(3, 4));
+    assert_eq!(a.signum(), Point::new(1, -1));
+
+    let p = Point3::new(1, -2, 3);
+    assert_eq!(p + p - Point3::new(1, 1, 1), Point3::new(1, -5, 5));
+    assert_eq!(-p * 2 / 2, Point3::new(-1, 2, -3));
+    assert_eq!(p.component_min(Point3::origin()), Point3::new(0, -2, 0));
+    assert_eq!(p.component_max(Point3::origin()).signum(), Point3::new(1, 0, 1));
+}
+
+#[test]
+fn point_rotation_test() {
+    assert_eq!(Point::up().rotate_cw(), Point::right());
+    assert_eq!(Point::right().rotate_cw(), Point::down());
+    assert_eq!(Point::up().rotate_ccw(), Point::left());
+    assert_eq!(Point::new(2, 1).rotate_cw().rotate_ccw(), Point::new(2, 1));
+    assert_eq!(Point::new(2, 1).reflect_x(), Point::new(-2, 1));
+    assert_eq!(Point::new(2, 1).reflect_y(), Point::new(2, -1));
+}
+
+#[test]
+fn direction_from_point_test() {
+    for &dir in &[Direction::Left, Direction::Right, Direction::Up, Direction::Down] {
+        assert_eq!(Direction::from_point(dir.as_point()), Some(dir));
+    }
+    assert_eq!(Direction::from_point(Point::new(1, 1)), None);
+    assert_eq!(Direction::from_point(Point::origin()), None);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Direction {
     Left,
     Right,
@@ -128,6 +573,11 @@ pub enum Direction {
 }
 
 impl Direction {
+    // All four directions, in the same reading order as Point::neighbors.
+    pub fn all() -> impl Iterator<Item = Direction> {
+        [Direction::Up, Direction::Left, Direction::Right, Direction::Down].iter().cloned()
+    }
+
     pub fn as_point(self) -> Point {
         match self {
             Direction::Left => Point::new(-1, 0),
@@ -136,13 +586,83 @@ impl Direction {
             Direction::Down => Point::new(0, 1),
         }
     }
+
+    // The inverse of as_point; returns None if the point is not a unit step.
+    pub fn from_point(point: Point) -> Option<Direction> {
+        match (point.x, point.y) {
+            (-1, 0) => Some(Direction::Left),
+            (1, 0) => Some(Direction::Right),
+            (0, -1) => Some(Direction::Up),
+            (0, 1) => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    // The inverse of to_char: accepts only the arrows ^v<>, so it is safe for picking markers
+    // out of a map that also contains letters.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '<' => Some(Direction::Left),
+            '>' => Some(Direction::Right),
+            '^' => Some(Direction::Up),
+            'v' => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    // Accepts arrows (^v<>), compass letters (NSEW) and UDLR, in either case.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c.to_ascii_uppercase() {
+            '<' | 'W' | 'L' => Some(Direction::Left),
+            '>' | 'E' | 'R' => Some(Direction::Right),
+            '^' | 'N' | 'U' => Some(Direction::Up),
+            'V' | 'S' | 'D' => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Left => '<',
+            Direction::Right => '>',
+            Direction::Up => '^',
+            Direction::Down => 'v',
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Turn {
     Straight,
     Left,
     Right,
+    Reverse,
+}
+
+impl Turn {
+    // The turn to take at the next intersection, cycling left, straight, right. None for Reverse,
+    // which is not part of the cycle.
+    pub fn next_intersection(self) -> Option<Turn> {
+        match self {
+            Turn::Left => Some(Turn::Straight),
+            Turn::Straight => Some(Turn::Right),
+            Turn::Right => Some(Turn::Left),
+            Turn::Reverse => None,
+        }
+    }
 }
 
 impl ops::Add<Turn> for Direction {
@@ -162,11 +682,150 @@ impl ops::Add<Turn> for Direction {
                 Direction::Up => Direction::Right,
                 Direction::Down => Direction::Left,
             }
+            Turn::Reverse => self.opposite(),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl ops::AddAssign<Turn> for Direction {
+    fn add_assign(&mut self, turn: Turn) {
+        *self = *self + turn;
+    }
+}
+
+// The turn needed to go from heading rhs to heading self, so that rhs + (self - rhs) == self.
+impl ops::Sub<Direction> for Direction {
+    type Output = Turn;
+    fn sub(self, rhs: Direction) -> Turn {
+        [Turn::Straight, Turn::Left, Turn::Right, Turn::Reverse].iter()
+            .cloned()
+            .find(|&turn| rhs + turn == self)
+            .unwrap()
+    }
+}
+
+// The eight compass directions, including diagonals, with north pointing up (negative y).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Compass {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+static COMPASS: [Compass; 8] = [
+    Compass::N, Compass::NE, Compass::E, Compass::SE, Compass::S, Compass::SW, Compass::W, Compass::NW,
+];
+
+impl Compass {
+    // All eight directions, clockwise starting from north.
+    pub fn all() -> impl Iterator<Item = Compass> {
+        COMPASS.iter().cloned()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn as_point(self) -> Point {
+        match self {
+            Compass::N => Point::new(0, -1),
+            Compass::NE => Point::new(1, -1),
+            Compass::E => Point::new(1, 0),
+            Compass::SE => Point::new(1, 1),
+            Compass::S => Point::new(0, 1),
+            Compass::SW => Point::new(-1, 1),
+            Compass::W => Point::new(-1, 0),
+            Compass::NW => Point::new(-1, -1),
+        }
+    }
+
+    pub fn from_point(point: Point) -> Option<Compass> {
+        Compass::all().find(|c| c.as_point() == point)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    // Rotates by 45 degrees.
+    pub fn rotate_cw(self) -> Compass {
+        COMPASS[(self.index() + 1) % 8]
+    }
+
+    pub fn rotate_ccw(self) -> Compass {
+        COMPASS[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Compass {
+        COMPASS[(self.index() + 4) % 8]
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Compass {
+        match direction {
+            Direction::Left => Compass::W,
+            Direction::Right => Compass::E,
+            Direction::Up => Compass::N,
+            Direction::Down => Compass::S,
+        }
+    }
+}
+
+#[test]
+fn direction_test() {
+    assert_eq!(Direction::all().map(Direction::as_point).collect::<Vec<_>>(), Point::origin().neighbors().to_vec());
+    for dir in Direction::all() {
+        assert_eq!(dir.opposite().as_point(), -dir.as_point());
+        assert_eq!(dir + Turn::Reverse, dir.opposite());
+        assert_eq!(Direction::from_char(dir.to_char()), Some(dir));
+        assert_eq!(Direction::from_arrow(dir.to_char()), Some(dir));
+        assert_eq!(dir + Turn::Left + Turn::Left, dir + Turn::Reverse);
+        assert_eq!((dir + Turn::Right).as_point(), dir.as_point().rotate_cw());
+    }
+    assert_eq!(Direction::from_char('N'), Some(Direction::Up));
+    assert_eq!(Direction::from_char('d'), Some(Direction::Down));
+    assert_eq!(Direction::from_char('x'), None);
+    assert_eq!(Direction::from_arrow('V'), None);
+    assert_eq!(Direction::from_arrow('N'), None);
+    assert_eq!(Turn::Left.next_intersection().and_then(Turn::next_intersection), Some(Turn::Right));
+    assert_eq!(Turn::Right.next_intersection(), Some(Turn::Left));
+    assert_eq!(Turn::Reverse.next_intersection(), None);
+}
+
+#[test]
+fn direction_sub_test() {
+    for a in Direction::all() {
+        for b in Direction::all() {
+            assert_eq!(b + (a - b), a);
+        }
+    }
+    assert_eq!(Direction::Left - Direction::Up, Turn::Left);
+    assert_eq!(Direction::Right - Direction::Up, Turn::Right);
+    assert_eq!(Direction::Down - Direction::Up, Turn::Reverse);
+    assert_eq!(Direction::Up - Direction::Up, Turn::Straight);
+}
+
+#[test]
+fn compass_test() {
+    assert_eq!(Compass::all().map(Compass::as_point).count(), 8);
+    for c in Compass::all() {
+        assert_eq!(Compass::from_point(c.as_point()), Some(c));
+        assert_eq!(c.opposite().as_point(), -c.as_point());
+        assert_eq!(c.rotate_cw().rotate_ccw(), c);
+        assert_eq!(c.is_diagonal(), c.as_point().x != 0 && c.as_point().y != 0);
+    }
+    assert_eq!(Compass::NW.rotate_cw(), Compass::N);
+    assert_eq!(Compass::from(Direction::Left), Compass::W);
+    assert_eq!(Compass::from_point(Point::new(2, 0)), None);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Rect {
     x_range: RangeInclusive<i32>,
     y_range: RangeInclusive<i32>,
@@ -211,8 +870,9 @@ impl Rect {
     pub fn x_max(&self) -> i32 { *self.x_range.end() }
     pub fn y_min(&self) -> i32 { *self.y_range.start() }
     pub fn y_max(&self) -> i32 { *self.y_range.end() }
-    pub fn width(&self) -> u32 { cmp::max(0i32, self.x_range.end() - self.x_range.start() + 1) as u32 }
-    pub fn height(&self) -> u32 { cmp::max(0i32, self.y_range.end() - self.y_range.start() + 1) as u32 }
+    // Computed in i64 so that the inverted ranges of an empty bounding box do not overflow.
+    pub fn width(&self) -> u32 { cmp::max(0i64, i64::from(self.x_max()) - i64::from(self.x_min()) + 1) as u32 }
+    pub fn height(&self) -> u32 { cmp::max(0i64, i64::from(self.y_max()) - i64::from(self.y_min()) + 1) as u32 }
 
     pub fn contains(&self, point: Point) -> bool {
         // Range::contains is nightly-only.
@@ -225,17 +885,82 @@ impl Rect {
             self.y_min() - top ..= self.y_max() + bottom)
     }
 
-    pub fn iter(&self) -> RectIter {
-        self.clone().into_iter()
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
     }
-}
 
-#[test]
-fn rect_contains_test() {
-    let rect = Rect::from_inclusive_ranges(1 ..= 3, 1 ..= 2);
-    assert!(rect.contains(Point::new(1, 1)));
-    assert!(rect.contains(Point::new(1, 1)));
-    assert!(!rect.contains(Point::new(-1, -1)));
+    pub fn area(&self) -> u64 {
+        u64::from(self.width()) * u64::from(self.height())
+    }
+
+    // The center point, rounded towards the top left if the size is even.
+    pub fn center(&self) -> Point {
+        Point::new((self.x_min() + self.x_max()).div_euclid(2), (self.y_min() + self.y_max()).div_euclid(2))
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.is_empty() || (
+            self.contains(Point::new(other.x_min(), other.y_min())) &&
+            self.contains(Point::new(other.x_max(), other.y_max())))
+    }
+
+    // The points contained in both rects. All empty intersections are returned as Rect::empty().
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let rect = Self::from_inclusive_ranges(
+            cmp::max(self.x_min(), other.x_min()) ..= cmp::min(self.x_max(), other.x_max()),
+            cmp::max(self.y_min(), other.y_min()) ..= cmp::min(self.y_max(), other.y_max()));
+        if rect.is_empty() { Rect::empty() } else { rect }
+    }
+
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    // Splits the points that are in self but not in other into at most four disjoint, non-empty
+    // rects: the full-width bands above and below other, and the pieces to its left and right.
+    pub fn subtract(&self, other: &Rect) -> Vec<Rect> {
+        let overlap = self.intersection(other);
+        if overlap.is_empty() {
+            return if self.is_empty() { vec![] } else { vec![self.clone()] };
+        }
+        vec![
+            Self::from_inclusive_ranges(self.x_range(), self.y_min() ..= overlap.y_min() - 1),
+            Self::from_inclusive_ranges(self.x_min() ..= overlap.x_min() - 1, overlap.y_range()),
+            Self::from_inclusive_ranges(overlap.x_max() + 1 ..= self.x_max(), overlap.y_range()),
+            Self::from_inclusive_ranges(self.x_range(), overlap.y_max() + 1 ..= self.y_max()),
+        ]
+            .into_iter()
+            .filter(|rect| !rect.is_empty())
+            .collect()
+    }
+
+    // Iterates in reading order: left to right, then top to bottom.
+    pub fn iter(&self) -> RectIter {
+        self.clone().into_iter()
+    }
+
+    // Iterates top to bottom, then left to right.
+    pub fn iter_columns(&self) -> RectIter {
+        RectIter::new(self.clone(), true)
+    }
+
+    // Iterates over the points on the edge of the rect, in reading order.
+    pub fn border(&self) -> impl Iterator<Item = Point> {
+        let rect = self.clone();
+        let inner_step = cmp::max(1, (rect.width() as usize).saturating_sub(1));
+        self.y_range().flat_map(move |y| {
+            let step = if y == rect.y_min() || y == rect.y_max() { 1 } else { inner_step };
+            rect.x_range().step_by(step).map(move |x| Point::new(x, y))
+        })
+    }
+}
+
+#[test]
+fn rect_contains_test() {
+    let rect = Rect::from_inclusive_ranges(1 ..= 3, 1 ..= 2);
+    assert!(rect.contains(Point::new(1, 1)));
+    assert!(rect.contains(Point::new(1, 1)));
+    assert!(!rect.contains(Point::new(-1, -1)));
     assert!(!rect.contains(Point::new(0, 0)));
     assert!(!rect.contains(Point::new(0, 1)));
     assert!(!rect.contains(Point::new(1, 0)));
@@ -260,37 +985,69 @@ impl<'a> FromIterator<&'a Point> for Rect {
     }
 }
 
-// Allows iterating over the Points contained in a Rect.
+// Allows iterating over the Points contained in a Rect, in reading order.
 impl IntoIterator for Rect {
     type Item = Point;
     type IntoIter = RectIter;
     fn into_iter(self) -> Self::IntoIter {
-        RectIter { rect: self.clone(), current_point: Point::new(self.x_max(), self.y_min() - 1) }
+        RectIter::new(self, false)
     }
 }
 
 pub struct RectIter {
     rect: Rect,
-    current_point: Point,
+    column_major: bool,
+    // Indices of the next points to be returned from the front and back, back being exclusive.
+    front: usize,
+    back: usize,
+}
+
+impl RectIter {
+    fn new(rect: Rect, column_major: bool) -> RectIter {
+        let back = if rect.is_empty() { 0 } else { rect.area() as usize };
+        RectIter { rect, column_major, front: 0, back }
+    }
+
+    fn point_at(&self, index: usize) -> Point {
+        let major = if self.column_major { self.rect.height() } else { self.rect.width() } as usize;
+        let (a, b) = ((index % major) as i32, (index / major) as i32);
+        if self.column_major {
+            Point::new(self.rect.x_min() + b, self.rect.y_min() + a)
+        } else {
+            Point::new(self.rect.x_min() + a, self.rect.y_min() + b)
+        }
+    }
 }
 
 impl Iterator for RectIter {
     type Item = Point;
 
     fn next(&mut self) -> Option<Point> {
-        self.current_point.x += 1;
-        if self.current_point.x > self.rect.x_max() {
-            self.current_point.x = self.rect.x_min();
-            self.current_point.y += 1;
+        if self.front >= self.back {
+            return None;
         }
-        if self.rect.contains(self.current_point) {
-            Some(self.current_point)
-        } else {
-            None
+        self.front += 1;
+        Some(self.point_at(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for RectIter {
+    fn next_back(&mut self) -> Option<Point> {
+        if self.front >= self.back {
+            return None;
         }
+        self.back -= 1;
+        Some(self.point_at(self.back))
     }
 }
 
+impl ExactSizeIterator for RectIter {}
+
 impl Display for Rect {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "({}..={}, {}..={})", self.x_min(), self.x_max(), self.y_min(), self.y_max())
@@ -315,8 +1072,240 @@ fn rect_into_iter_test() {
     ]);
 }
 
+#[test]
+fn rect_iter_order_test() {
+    let rect = Rect::from_inclusive_ranges(0..=2, 5..=6);
+    assert_eq!(rect.iter().len(), 6);
+    assert_eq!(rect.iter().rev().take(2).collect::<Vec<Point>>(), vec![Point::new(2, 6), Point::new(1, 6)]);
+    assert_eq!(rect.iter_columns().collect::<Vec<Point>>(), vec![
+        Point::new(0, 5), Point::new(0, 6), Point::new(1, 5), Point::new(1, 6), Point::new(2, 5), Point::new(2, 6),
+    ]);
+    let mut iter = rect.iter();
+    assert_eq!(iter.next(), Some(Point::new(0, 5)));
+    assert_eq!(iter.next_back(), Some(Point::new(2, 6)));
+    assert_eq!(iter.len(), 4);
+    assert_eq!(Rect::empty().iter().len(), 0);
+}
+
+#[test]
+fn rect_border_test() {
+    let rect = Rect::from_inclusive_ranges(0..=2, 0..=2);
+    assert_eq!(rect.border().count(), 8);
+    assert!(!rect.border().any(|p| p == Point::new(1, 1)));
+    assert_eq!(Rect::from_inclusive_ranges(0..=0, 0..=2).border().count(), 3);
+    assert_eq!(Rect::from_inclusive_ranges(0..=3, 0..=0).border().count(), 4);
+    assert_eq!(Rect::empty().border().count(), 0);
+}
+
+#[test]
+fn rect_set_operations_test() {
+    let a = Rect::from_inclusive_ranges(0..=3, 0..=3);
+    let b = Rect::from_inclusive_ranges(2..=5, -1..=1);
+    assert_eq!(a.intersection(&b), Rect::from_inclusive_ranges(2..=3, 0..=1));
+    assert!(a.overlaps(&b));
+    assert!(!a.overlaps(&Rect::from_inclusive_ranges(4..=5, 0..=0)));
+    assert_eq!(a.intersection(&Rect::from_inclusive_ranges(4..=5, 0..=0)), Rect::empty());
+    assert!(a.contains_rect(&Rect::from_inclusive_ranges(1..=3, 0..=2)));
+    assert!(!a.contains_rect(&b));
+    assert!(a.contains_rect(&Rect::empty()));
+    assert_eq!(a.area(), 16);
+    assert!(Rect::empty().is_empty());
+    assert!(std::iter::empty::<&Point>().collect::<Rect>().is_empty());
+    assert_eq!(a.center(), Point::new(1, 1));
+    assert_eq!(Rect::from_inclusive_ranges(-3..=-2, 1..=1).center(), Point::new(-3, 1));
+
+    let pieces = a.subtract(&b);
+    assert_eq!(pieces.iter().map(Rect::area).sum::<u64>(), a.area() - 4);
+    for (i, piece) in pieces.iter().enumerate() {
+        assert!(a.contains_rect(piece));
+        assert!(!piece.overlaps(&b));
+        assert!(pieces[i + 1..].iter().all(|other| !piece.overlaps(other)));
+    }
+    assert_eq!(a.subtract(&a), vec![]);
+    assert_eq!(a.subtract(&Rect::from_inclusive_ranges(9..=9, 9..=9)), vec![a.clone()]);
+    assert_eq!(a.subtract(&Rect::from_inclusive_ranges(1..=2, 1..=2)).len(), 4);
+}
+
+// An axis-aligned box in 3D space, the 3D analogue of Rect.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Box3 {
+    x_range: RangeInclusive<i32>,
+    y_range: RangeInclusive<i32>,
+    z_range: RangeInclusive<i32>,
+}
+
+fn range_len(range: &RangeInclusive<i32>) -> u64 {
+    cmp::max(0i64, i64::from(*range.end()) - i64::from(*range.start()) + 1) as u64
+}
+
+// Splits a range into two halves, or leaves it whole if it contains a single value.
+fn split_range(range: &RangeInclusive<i32>) -> Vec<RangeInclusive<i32>> {
+    let (start, end) = (*range.start(), *range.end());
+    if start >= end {
+        return vec![range.clone()];
+    }
+    let mid = (i64::from(start) + i64::from(end)).div_euclid(2) as i32;
+    vec![start ..= mid, mid + 1 ..= end]
+}
+
+// The distance from value to the nearest value in the range, or 0 if it is inside.
+fn range_distance(range: &RangeInclusive<i32>, value: i32) -> u32 {
+    if value < *range.start() {
+        (i64::from(*range.start()) - i64::from(value)) as u32
+    } else if value > *range.end() {
+        (i64::from(value) - i64::from(*range.end())) as u32
+    } else {
+        0
+    }
+}
+
+impl Box3 {
+    pub fn from_inclusive_ranges(x_range: RangeInclusive<i32>, y_range: RangeInclusive<i32>, z_range: RangeInclusive<i32>) -> Box3 {
+        Box3 { x_range, y_range, z_range }
+    }
+
+    pub fn from_corners(min: Point3, max: Point3) -> Box3 {
+        Box3::from_inclusive_ranges(min.x ..= max.x, min.y ..= max.y, min.z ..= max.z)
+    }
+
+    pub fn x_range(&self) -> RangeInclusive<i32> { self.x_range.clone() }
+    pub fn y_range(&self) -> RangeInclusive<i32> { self.y_range.clone() }
+    pub fn z_range(&self) -> RangeInclusive<i32> { self.z_range.clone() }
+    pub fn min(&self) -> Point3 { Point3::new(*self.x_range.start(), *self.y_range.start(), *self.z_range.start()) }
+    pub fn max(&self) -> Point3 { Point3::new(*self.x_range.end(), *self.y_range.end(), *self.z_range.end()) }
+
+    pub fn is_empty(&self) -> bool {
+        self.volume() == 0
+    }
+
+    pub fn volume(&self) -> u64 {
+        range_len(&self.x_range) * range_len(&self.y_range) * range_len(&self.z_range)
+    }
+
+    pub fn contains(&self, point: &Point3) -> bool {
+        self.distance_to(point) == 0 && !self.is_empty()
+    }
+
+    // The Manhattan distance from the point to the nearest point in the box.
+    pub fn distance_to(&self, point: &Point3) -> u32 {
+        range_distance(&self.x_range, point.x) + range_distance(&self.y_range, point.y) + range_distance(&self.z_range, point.z)
+    }
+
+    // Whether any point in the box lies within the given Manhattan distance of center.
+    pub fn intersects_ball(&self, center: &Point3, radius: u32) -> bool {
+        !self.is_empty() && self.distance_to(center) <= radius
+    }
+
+    // Splits the box into up to eight non-empty octants by halving each axis. Axes that are only
+    // one unit long are not split, so a single point yields just itself.
+    pub fn octants(&self) -> Vec<Box3> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let mut octants = vec![];
+        for x_range in split_range(&self.x_range) {
+            for y_range in split_range(&self.y_range) {
+                for z_range in split_range(&self.z_range) {
+                    octants.push(Box3::from_inclusive_ranges(x_range.clone(), y_range.clone(), z_range));
+                }
+            }
+        }
+        octants
+    }
+}
+
+impl Display for Box3 {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "({}..={}, {}..={}, {}..={})",
+            self.x_range.start(), self.x_range.end(),
+            self.y_range.start(), self.y_range.end(),
+            self.z_range.start(), self.z_range.end())
+    }
+}
+
+// Finds the point that lies within the most of the given L1 balls (center and radius), breaking
+// ties by the smallest Manhattan distance to the origin. Returns the point and the number of balls
+// containing it.
+//
+// This is a best-first octree search: boxes are explored in order of the number of balls they
+// intersect, which is an upper bound for any point inside, so the first single point popped from
+// the queue is optimal.
+pub fn most_covered_point(balls: &[(Point3, u32)]) -> Option<(Point3, usize)> {
+    if balls.is_empty() {
+        return None;
+    }
+    let clamp = |v: i64| cmp::max(i64::from(i32::MIN), cmp::min(i64::from(i32::MAX), v)) as i32;
+    let min = balls.iter()
+        .map(|(c, r)| Point3::new(clamp(i64::from(c.x) - i64::from(*r)), clamp(i64::from(c.y) - i64::from(*r)), clamp(i64::from(c.z) - i64::from(*r))))
+        .fold(balls[0].0, Point3::component_min);
+    let max = balls.iter()
+        .map(|(c, r)| Point3::new(clamp(i64::from(c.x) + i64::from(*r)), clamp(i64::from(c.y) + i64::from(*r)), clamp(i64::from(c.z) + i64::from(*r))))
+        .fold(balls[0].0, Point3::component_max);
+
+    let origin = Point3::origin();
+    let mut boxes = vec![];
+    let mut queue = BinaryHeap::new();
+    let push = |b: Box3, boxes: &mut Vec<Box3>, queue: &mut BinaryHeap<_>| {
+        let count = balls.iter().filter(|(c, r)| b.intersects_ball(c, *r)).count();
+        queue.push((count, Reverse(b.distance_to(&origin)), Reverse(b.volume()), Reverse(boxes.len())));
+        boxes.push(b);
+    };
+    push(Box3::from_corners(min, max), &mut boxes, &mut queue);
+    while let Some((count, _, Reverse(volume), Reverse(index))) = queue.pop() {
+        if volume == 1 {
+            return Some((boxes[index].min(), count));
+        }
+        for octant in boxes[index].octants() {
+            push(octant, &mut boxes, &mut queue);
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn box3_test() {
+    let b = Box3::from_inclusive_ranges(0..=3, -1..=1, 5..=5);
+    assert_eq!(b.volume(), 12);
+    assert!(!b.is_empty());
+    assert!(Box3::from_inclusive_ranges(0..=-1, 0..=0, 0..=0).is_empty());
+    assert!(b.contains(&Point3::new(3, 0, 5)));
+    assert!(!b.contains(&Point3::new(3, 0, 4)));
+    assert_eq!(b.distance_to(&Point3::new(1, 0, 5)), 0);
+    assert_eq!(b.distance_to(&Point3::new(-2, 3, 0)), 2 + 2 + 5);
+    assert!(b.intersects_ball(&Point3::new(-2, 3, 0), 9));
+    assert!(!b.intersects_ball(&Point3::new(-2, 3, 0), 8));
+    assert_eq!(b.min(), Point3::new(0, -1, 5));
+    assert_eq!(b.max(), Point3::new(3, 1, 5));
+}
+
+#[test]
+fn box3_octants_test() {
+    let b = Box3::from_inclusive_ranges(0..=3, -1..=1, 5..=5);
+    let octants = b.octants();
+    assert_eq!(octants.len(), 4);
+    assert_eq!(octants.iter().map(Box3::volume).sum::<u64>(), b.volume());
+    assert_eq!(octants[0], Box3::from_inclusive_ranges(0..=1, -1..=0, 5..=5));
+    let point = Box3::from_corners(Point3::new(1, 2, 3), Point3::new(1, 2, 3));
+    assert_eq!(point.octants(), vec![point.clone()]);
+}
+
+#[test]
+fn most_covered_point_test() {
+    let balls = [
+        (Point3::new(10, 12, 12), 2),
+        (Point3::new(12, 14, 12), 2),
+        (Point3::new(16, 12, 12), 4),
+        (Point3::new(14, 14, 14), 6),
+        (Point3::new(50, 50, 50), 200),
+        (Point3::new(10, 10, 10), 5),
+    ];
+    assert_eq!(most_covered_point(&balls), Some((Point3::new(12, 12, 12), 5)));
+    assert_eq!(most_covered_point(&[(Point3::new(0, -5, 0), 3)]), Some((Point3::new(0, -2, 0), 1)));
+    assert_eq!(most_covered_point(&[]), None);
+}
+
 // A dense 2D rectangular array with customizable lower bound.
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Hash)]
 pub struct Matrix<T> {
     rect: Rect,
     values: Vec<T>,
@@ -326,7 +1315,11 @@ impl<T> Matrix<T> {
     pub fn new(rect: &Rect, initial_value: T) -> Matrix<T>
         where T: Clone
     {
-        Matrix { rect: rect.clone(), values: vec![initial_value; (rect.width() * rect.height()) as usize] }
+        Matrix { rect: rect.clone(), values: vec![initial_value; rect.area() as usize] }
+    }
+
+    pub fn into_growable(self, fill: T) -> GrowableMatrix<T> {
+        GrowableMatrix { rect: self.rect.clone(), storage: self, fill }
     }
 
     pub fn rect(&self) -> &Rect {
@@ -360,16 +1353,90 @@ impl<T> Matrix<T> {
         &self.values[start..end]
     }
 
+    pub fn column(&self, x: i32) -> impl Iterator<Item=&T> {
+        self.rect.y_range().map(move |y| &self[Point::new(x, y)])
+    }
+
+    // A borrowed view of the cells inside rect, which must lie within this matrix.
+    pub fn view(&self, rect: &Rect) -> MatrixView<'_, T> {
+        assert!(self.rect.contains_rect(rect), "{} not inside bounds {}", rect, self.rect);
+        MatrixView { matrix: self, rect: rect.clone() }
+    }
+
+    // Copies all cells of src into this matrix, shifted by offset. Cells that end up outside this
+    // matrix are dropped.
+    pub fn blit(&mut self, src: &Matrix<T>, offset: Point)
+        where T: Clone
+    {
+        for point in src.coords() {
+            if let Some(cell) = self.get_mut(point + offset) {
+                *cell = src[point].clone();
+            }
+        }
+    }
+
+    // The transforms below all keep the top left corner of the rect in place, swapping width and
+    // height where needed.
+
+    pub fn transpose(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let (x0, y0) = (self.rect.x_min(), self.rect.y_min());
+        self.transformed(self.rect.height(), self.rect.width(), |dx, dy| Point::new(x0 + dy, y0 + dx))
+    }
+
+    pub fn rotate_cw(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let (x0, y0, h) = (self.rect.x_min(), self.rect.y_min(), self.rect.height() as i32);
+        self.transformed(self.rect.height(), self.rect.width(), |dx, dy| Point::new(x0 + dy, y0 + h - 1 - dx))
+    }
+
+    pub fn rotate_ccw(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let (x0, y0, w) = (self.rect.x_min(), self.rect.y_min(), self.rect.width() as i32);
+        self.transformed(self.rect.height(), self.rect.width(), |dx, dy| Point::new(x0 + w - 1 - dy, y0 + dx))
+    }
+
+    // Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let (x0, y0, w) = (self.rect.x_min(), self.rect.y_min(), self.rect.width() as i32);
+        self.transformed(self.rect.width(), self.rect.height(), |dx, dy| Point::new(x0 + w - 1 - dx, y0 + dy))
+    }
+
+    // Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let (x0, y0, h) = (self.rect.x_min(), self.rect.y_min(), self.rect.height() as i32);
+        self.transformed(self.rect.width(), self.rect.height(), |dx, dy| Point::new(x0 + dx, y0 + h - 1 - dy))
+    }
+
+    // Builds a width by height matrix with the same top left corner, where source(dx, dy) gives
+    // the point in self to take the cell at offset (dx, dy) from.
+    fn transformed<F>(&self, width: u32, height: u32, source: F) -> Matrix<T>
+        where F: Fn(i32, i32) -> Point, T: Clone
+    {
+        let (x0, y0) = (self.rect.x_min(), self.rect.y_min());
+        let rect = Rect::from_exclusive_ranges(x0 .. x0 + width as i32, y0 .. y0 + height as i32);
+        let values = rect.iter().map(|p| self[source(p.x - x0, p.y - y0)].clone()).collect();
+        Matrix { rect, values }
+    }
+
     pub fn map<U, F>(&self, f: F) -> Matrix<U>
         where F: Fn(&T) -> U, U: Clone + Default
     {
         let mut out = Matrix::new(&self.rect, Default::default());
-        for i in 0..self.values.len() {
-            out.values[i] = f(&self.values[i]);
+        for (i, point) in self.rect.iter().enumerate() {
+            out.values[i] = f(&self.values[self.index_of(point)]);
         }
         out
     }
 
+    // The values in reading order.
     pub fn as_slice(&self) -> &[T] {
         self.values.as_slice()
     }
@@ -382,11 +1449,99 @@ impl<T> Matrix<T> {
         }
     }
 
+    // Trims rows and columns off the edges as long as all their cells match the predicate.
+    pub fn shrink_to_fit<F>(&mut self, predicate: F)
+        where F: Fn(&T) -> bool, T: Clone
+    {
+        let rect = trimmed_rect(&self.rect, |point| predicate(&self[point]));
+        if rect != self.rect {
+            *self = self.view(&rect).to_matrix();
+        }
+    }
+
+    // The orthogonal neighbors of point that lie inside the rect, in reading order.
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item=(Point, &T)> {
+        let neighbors = point.neighbors();
+        (0..neighbors.len()).filter_map(move |i| self.get(neighbors[i]).map(|value| (neighbors[i], value)))
+    }
+
+    // The orthogonal and diagonal neighbors of point that lie inside the rect, clockwise from the
+    // top.
+    pub fn neighbors_diagonal(&self, point: Point) -> impl Iterator<Item=(Point, &T)> {
+        let neighbors = point.neighbors_diagonal();
+        (0..neighbors.len()).filter_map(move |i| self.get(neighbors[i]).map(|value| (neighbors[i], value)))
+    }
+
+    // Computes a new matrix of the same size, as in one step of a cellular automaton. The in-bounds
+    // orthogonal and diagonal neighbors of each cell are folded into a copy of init, and f is
+    // called with the cell and the result, e.g. a count of each kind of neighbor.
+    pub fn map_neighborhood<A, U, G, F>(&self, init: A, fold: G, f: F) -> Matrix<U>
+        where A: Clone, G: Fn(A, &T) -> A, F: Fn(&T, A) -> U
+    {
+        // Cells away from the border have all eight neighbors, which can be found by their offset
+        // in values without any bounds checks on the points.
+        let width = self.rect.width() as isize;
+        let offsets = [-width - 1, -width, -width + 1, -1, 1, width - 1, width, width + 1];
+        let interior = self.rect.padded(-1, -1, -1, -1);
+        let values = self.rect.iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let mut acc = init.clone();
+                if interior.contains(point) {
+                    for &offset in &offsets {
+                        acc = fold(acc, &self.values[(index as isize + offset) as usize]);
+                    }
+                } else {
+                    for &neighbor in &point.neighbors_diagonal() {
+                        if let Some(value) = self.get(neighbor) {
+                            acc = fold(acc, value);
+                        }
+                    }
+                }
+                f(&self.values[index], acc)
+            })
+            .collect();
+        Matrix { rect: self.rect.clone(), values }
+    }
+
+    // Precomputes sums over the matrix so that the sum of any rect can be found in constant time.
+    pub fn summed_area(&self) -> SummedArea
+        where T: Copy + Into<i64>
+    {
+        let mut sums = Matrix::new(&self.rect.padded(1, 0, 1, 0), 0i64);
+        for point in self.rect.iter() {
+            sums[point] = self[point].into()
+                + sums[point + Point::left()]
+                + sums[point + Point::up()]
+                - sums[point + Point::left() + Point::up()];
+        }
+        SummedArea { sums }
+    }
+
     fn index_of(&self, point: Point) -> usize {
         ((point.y - self.rect.y_min()) * self.rect.width() as i32 + point.x - self.rect.x_min()) as usize
     }
 }
 
+// The largest rect within rect whose border rows and columns are not entirely empty.
+fn trimmed_rect<F: Fn(Point) -> bool>(rect: &Rect, is_empty: F) -> Rect {
+    let all_empty = |rect: Rect| rect.iter().all(&is_empty);
+    let mut rect = rect.clone();
+    while !rect.is_empty() && all_empty(Rect::from_inclusive_ranges(rect.x_range(), rect.y_min() ..= rect.y_min())) {
+        rect = rect.padded(0, 0, -1, 0);
+    }
+    while !rect.is_empty() && all_empty(Rect::from_inclusive_ranges(rect.x_range(), rect.y_max() ..= rect.y_max())) {
+        rect = rect.padded(0, 0, 0, -1);
+    }
+    while !rect.is_empty() && all_empty(Rect::from_inclusive_ranges(rect.x_min() ..= rect.x_min(), rect.y_range())) {
+        rect = rect.padded(-1, 0, 0, 0);
+    }
+    while !rect.is_empty() && all_empty(Rect::from_inclusive_ranges(rect.x_max() ..= rect.x_max(), rect.y_range())) {
+        rect = rect.padded(0, -1, 0, 0);
+    }
+    if rect.is_empty() { Rect::empty() } else { rect }
+}
+
 impl<T> ops::Index<Point> for Matrix<T> {
     type Output = T;
     fn index(&self, point: Point) -> &T {
@@ -422,6 +1577,327 @@ fn test_matrix_index() {
     assert_eq!(mat.get(Point::new(3, 3)), None);
 }
 
+#[test]
+fn test_matrix_shrink_to_fit() {
+    let mut mat: Matrix<u8> = vec!["....", ".#..", "..#.", "...."].into_iter().collect();
+    mat.shrink_to_fit(|&c| c == b'.');
+    assert_eq!(mat.rect(), &Rect::from_inclusive_ranges(1 ..= 2, 1 ..= 2));
+    assert_eq!(mat.to_string(), "#.\n.#");
+    assert_eq!(mat.as_slice(), b"#..#");
+    mat.shrink_to_fit(|_| true);
+    assert!(mat.rect().is_empty());
+}
+
+// A Matrix that grows to contain every cell written to it, for when the bounds are not known in
+// advance. Reading outside its rect returns the fill value.
+#[derive(Clone)]
+pub struct GrowableMatrix<T> {
+    rect: Rect,
+    // Contains rect, with spare room around it so that repeatedly growing in the same direction
+    // takes amortized constant time. Cells outside rect always hold the fill value.
+    storage: Matrix<T>,
+    fill: T,
+}
+
+impl<T: Clone> GrowableMatrix<T> {
+    // Creates a matrix covering rect, with all cells set to fill.
+    pub fn new(rect: &Rect, fill: T) -> GrowableMatrix<T> {
+        Matrix::new(rect, fill.clone()).into_growable(fill)
+    }
+
+    pub fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    pub fn fill(&self) -> &T {
+        &self.fill
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item=Point> {
+        self.rect.iter()
+    }
+
+    // Unlike indexing, returns None outside the rect.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.rect.contains(point) { self.storage.get(point) } else { None }
+    }
+
+    pub fn row(&self, y: i32) -> &[T] {
+        self.view().row(y)
+    }
+
+    // The cells inside the rect.
+    pub fn view(&self) -> MatrixView<'_, T> {
+        self.storage.view(&self.rect)
+    }
+
+    pub fn into_matrix(self) -> Matrix<T> {
+        if *self.storage.rect() == self.rect { self.storage } else { self.view().to_matrix() }
+    }
+
+    pub fn fill_rect(&mut self, rect: &Rect, value: T) {
+        self.grow_to_contain(rect);
+        self.storage.fill_rect(rect, value);
+    }
+
+    // Copies all cells of src into this matrix, shifted by offset.
+    pub fn blit(&mut self, src: &Matrix<T>, offset: Point) {
+        self.grow_to_contain(&Rect::from_inclusive_ranges(
+            src.rect().x_min() + offset.x ..= src.rect().x_max() + offset.x,
+            src.rect().y_min() + offset.y ..= src.rect().y_max() + offset.y));
+        self.storage.blit(src, offset);
+    }
+
+    // Extends the rect to include the given rect, filling new cells with the fill value.
+    pub fn grow_to_contain(&mut self, rect: &Rect) {
+        if rect.is_empty() || self.rect.contains_rect(rect) {
+            return;
+        }
+        let new_rect = Rect::bounding_rects(&self.rect, rect);
+        let storage_rect = self.storage.rect().clone();
+        if !storage_rect.contains_rect(&new_rect) {
+            let target = Rect::bounding_rects(&storage_rect, &new_rect);
+            let (width, height) = (target.width() as i32, target.height() as i32);
+            let padded = target.padded(
+                if new_rect.x_min() < storage_rect.x_min() { width } else { 0 },
+                if new_rect.x_max() > storage_rect.x_max() { width } else { 0 },
+                if new_rect.y_min() < storage_rect.y_min() { height } else { 0 },
+                if new_rect.y_max() > storage_rect.y_max() { height } else { 0 });
+            let mut storage = Matrix::new(&padded, self.fill.clone());
+            for point in self.rect.iter() {
+                storage[point] = self.storage[point].clone();
+            }
+            self.storage = storage;
+        }
+        self.rect = new_rect;
+    }
+
+    // Trims rows and columns off the edges as long as all their cells match the predicate, and
+    // releases any spare storage.
+    pub fn shrink_to_fit<F>(&mut self, predicate: F)
+        where F: Fn(&T) -> bool
+    {
+        self.rect = trimmed_rect(&self.rect, |point| predicate(&self.storage[point]));
+        self.storage = self.view().to_matrix();
+    }
+}
+
+impl<T> ops::Index<Point> for GrowableMatrix<T> {
+    type Output = T;
+    fn index(&self, point: Point) -> &T {
+        if self.rect.contains(point) { &self.storage[point] } else { &self.fill }
+    }
+}
+
+impl<T: Clone> ops::IndexMut<Point> for GrowableMatrix<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.grow_to_contain(&Rect::from_inclusive_ranges(point.x ..= point.x, point.y ..= point.y));
+        &mut self.storage[point]
+    }
+}
+
+impl Display for GrowableMatrix<u8> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.view())
+    }
+}
+
+#[test]
+fn test_growable_matrix() {
+    let mut mat = GrowableMatrix::new(&Rect::empty(), b'.');
+    assert_eq!(mat[Point::new(5, 5)], b'.');
+    assert_eq!(mat.get(Point::new(5, 5)), None);
+    mat[Point::new(1, 1)] = b'a';
+    assert_eq!(mat.rect(), &Rect::from_inclusive_ranges(1 ..= 1, 1 ..= 1));
+    for x in 2..100 {
+        mat[Point::new(x, 0)] = b'b';
+    }
+    mat[Point::new(-3, 2)] = b'c';
+    assert_eq!(mat.rect(), &Rect::from_inclusive_ranges(-3 ..= 99, 0 ..= 2));
+    assert_eq!(mat[Point::new(1, 1)], b'a');
+    assert_eq!(mat[Point::new(50, 0)], b'b');
+    assert_eq!(mat[Point::new(-3, 2)], b'c');
+    assert_eq!(mat[Point::new(0, 0)], b'.');
+    assert_eq!(mat.row(2).len(), 103);
+
+    let sprite: Matrix<u8> = vec!["ab", "cd"].into_iter().collect();
+    let mut mat = GrowableMatrix::new(&Rect::empty(), b'.');
+    mat.blit(&sprite, Point::new(-1, -1));
+    mat.blit(&sprite, Point::new(1, 1));
+    assert_eq!(mat.to_string(), "ab..\ncd..\n..ab\n..cd");
+}
+
+#[test]
+fn test_growable_matrix_shrink_to_fit() {
+    let mut mat = GrowableMatrix::new(&Rect::empty(), 0);
+    mat[Point::new(-10, -10)] = 1;
+    mat[Point::new(10, 10)] = 0;
+    mat.shrink_to_fit(|&c| c == 0);
+    assert_eq!(mat.rect(), &Rect::from_inclusive_ranges(-10 ..= -10, -10 ..= -10));
+    assert_eq!(mat.clone().into_matrix().as_slice(), &[1]);
+    assert_eq!(mat[Point::new(10, 10)], 0);
+    mat.shrink_to_fit(|&c| c == 1);
+    assert!(mat.rect().is_empty());
+}
+
+// A borrowed rectangular part of a Matrix. It is indexed with the same coordinates as the
+// matrix it was taken from.
+pub struct MatrixView<'a, T> {
+    matrix: &'a Matrix<T>,
+    rect: Rect,
+}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    pub fn coords(&self) -> impl Iterator<Item=Point> {
+        self.rect.iter()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        if self.rect.contains(point) {
+            self.matrix.get(point)
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, y: i32) -> &'a [T] {
+        assert!(self.rect.y_range().contains(&y), "row {} not inside bounds {}", y, self.rect);
+        let row = self.matrix.row(y);
+        let start = (self.rect.x_min() - self.matrix.rect.x_min()) as usize;
+        &row[start .. start + self.rect.width() as usize]
+    }
+
+    pub fn column(&self, x: i32) -> impl Iterator<Item=&'a T> {
+        assert!(self.rect.x_range().contains(&x), "column {} not inside bounds {}", x, self.rect);
+        let matrix = self.matrix;
+        self.rect.y_range().map(move |y| &matrix[Point::new(x, y)])
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T>
+        where T: Clone
+    {
+        let values = self.rect.iter().map(|point| self[point].clone()).collect();
+        Matrix { rect: self.rect.clone(), values }
+    }
+}
+
+impl<'a, T> ops::Index<Point> for MatrixView<'a, T> {
+    type Output = T;
+    fn index(&self, point: Point) -> &T {
+        assert!(self.rect.contains(point), "{} not inside bounds {}", point, self.rect);
+        &self.matrix[point]
+    }
+}
+
+impl<'a> Display for MatrixView<'a, u8> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_matrix())
+    }
+}
+
+#[test]
+fn test_matrix_transforms() {
+    let mat: Matrix<u8> = vec!["abc", "def"].into_iter().collect::<Matrix<u8>>();
+    assert_eq!(mat.transpose().to_string(), "ad\nbe\ncf");
+    assert_eq!(mat.rotate_cw().to_string(), "da\neb\nfc");
+    assert_eq!(mat.rotate_ccw().to_string(), "cf\nbe\nad");
+    assert_eq!(mat.flip_horizontal().to_string(), "cba\nfed");
+    assert_eq!(mat.flip_vertical().to_string(), "def\nabc");
+    assert!(mat.rotate_cw().rotate_cw() == mat.flip_horizontal().flip_vertical());
+    assert!(mat.rotate_cw().rotate_ccw() == mat);
+    assert!(mat.transpose().transpose() == mat);
+    assert_eq!(mat.column(1).cloned().collect::<Vec<u8>>(), b"be".to_vec());
+
+    let mut shifted = Matrix::new(&Rect::from_inclusive_ranges(5 ..= 7, -2 ..= -1), b'.');
+    shifted.blit(&mat, Point::new(5, -2));
+    assert_eq!(shifted.transpose().rect(), &Rect::from_inclusive_ranges(5 ..= 6, -2 ..= 0));
+    assert_eq!(shifted.rotate_cw()[Point::new(5, -2)], b'd');
+}
+
+#[test]
+fn test_matrix_view() {
+    let mat: Matrix<u8> = vec!["abcd", "efgh", "ijkl"].into_iter().collect();
+    let view = mat.view(&Rect::from_inclusive_ranges(1 ..= 2, 1 ..= 2));
+    assert_eq!(view[Point::new(1, 1)], b'f');
+    assert_eq!(view.get(Point::new(0, 0)), None);
+    assert_eq!(view.row(2), b"jk");
+    assert_eq!(view.column(2).cloned().collect::<Vec<u8>>(), b"gk".to_vec());
+    assert_eq!(view.to_string(), "fg\njk");
+    assert_eq!(view.coords().count(), 4);
+}
+
+#[test]
+fn test_matrix_blit() {
+    let mut mat = Matrix::new(&Rect::from_inclusive_ranges(0 ..= 3, 0 ..= 2), b'.');
+    let sprite: Matrix<u8> = vec!["ab", "cd"].into_iter().collect();
+    mat.blit(&sprite, Point::new(1, 0));
+    mat.blit(&sprite, Point::new(3, 2));
+    assert_eq!(mat.to_string(), ".ab.\n.cd.\n...a");
+}
+
+// A summed-area table: each cell holds the sum of all cells above and to the left of it,
+// inclusive. There is an extra row and column of zeros at the top left.
+pub struct SummedArea {
+    sums: Matrix<i64>,
+}
+
+impl SummedArea {
+    // The sum of all cells in rect. Parts of rect outside the original matrix count as zero.
+    pub fn rect_sum(&self, rect: &Rect) -> i64 {
+        let rect = rect.intersection(&self.sums.rect().padded(-1, 0, -1, 0));
+        if rect.is_empty() {
+            return 0;
+        }
+        let (x0, x1, y0, y1) = (rect.x_min() - 1, rect.x_max(), rect.y_min() - 1, rect.y_max());
+        self.sums[Point::new(x1, y1)] - self.sums[Point::new(x0, y1)] - self.sums[Point::new(x1, y0)] + self.sums[Point::new(x0, y0)]
+    }
+}
+
+#[test]
+fn test_matrix_neighbors() {
+    let mat: Matrix<u8> = vec!["abc", "def"].into_iter().collect();
+    assert_eq!(mat.neighbors(Point::new(0, 0)).collect::<Vec<_>>(), vec![
+        (Point::new(1, 0), &b'b'), (Point::new(0, 1), &b'd'),
+    ]);
+    assert_eq!(mat.neighbors(Point::new(1, 1)).map(|(_, &c)| c).collect::<Vec<_>>(), b"bdf".to_vec());
+    assert_eq!(mat.neighbors_diagonal(Point::new(1, 1)).map(|(_, &c)| c).collect::<Vec<_>>(), b"bcfda".to_vec());
+    assert_eq!(mat.neighbors_diagonal(Point::new(5, 5)).count(), 0);
+}
+
+#[test]
+fn test_matrix_map_neighborhood() {
+    let mat: Matrix<u8> = vec!["#..", ".#.", "..."].into_iter().collect();
+    let counts = mat.map_neighborhood(0, |count, &c| count + (c == b'#') as usize, |_, count| count);
+    assert_eq!(counts.as_slice(), &[1, 2, 1, 2, 1, 1, 1, 1, 1]);
+    let mat: Matrix<u8> = vec!["#..#", ".#..", "..#.", "#..#"].into_iter().collect();
+    let counts = mat.map_neighborhood(0, |count, &c| count + (c == b'#') as usize, |_, count| count);
+    assert_eq!(counts.as_slice(), &[1, 2, 2, 0, 2, 2, 3, 2, 2, 3, 2, 2, 0, 2, 2, 1]);
+}
+
+#[test]
+fn test_summed_area() {
+    let mut mat = Matrix::new(&Rect::from_inclusive_ranges(-1 ..= 2, 3 ..= 5), 0);
+    for (i, point) in mat.rect().iter().enumerate() {
+        mat[point] = i as i32;
+    }
+    let sums = mat.summed_area();
+    for rect in &[
+        Rect::from_inclusive_ranges(-1 ..= 2, 3 ..= 5),
+        Rect::from_inclusive_ranges(0 ..= 1, 4 ..= 5),
+        Rect::from_inclusive_ranges(2 ..= 2, 3 ..= 3),
+        Rect::from_inclusive_ranges(-5 ..= 0, 0 ..= 4),
+    ] {
+        let expected: i64 = rect.iter().filter_map(|p| mat.get(p)).map(|&v| i64::from(v)).sum();
+        assert_eq!(sums.rect_sum(rect), expected, "{}", rect);
+    }
+    assert_eq!(sums.rect_sum(&Rect::from_inclusive_ranges(10 ..= 11, 3 ..= 5)), 0);
+    assert_eq!(sums.rect_sum(&Rect::empty()), 0);
+}
+
 // Creates a Matrix from lines of text. All lines must be equal in length. The top left cell will
 // be (0, 0).
 impl<'a> FromIterator<&'a str> for Matrix<u8> {
@@ -429,17 +1905,130 @@ impl<'a> FromIterator<&'a str> for Matrix<u8> {
         let lines: Vec<&str> = iter.into_iter().collect();
         let height = lines.len();
         if height == 0 {
-            return Matrix { rect: Rect::from_exclusive_ranges(0..0, 0..0), values: vec![] };
+            return Matrix { rect: Rect::empty(), values: vec![] };
         }
         let width = lines[0].len();
         assert!(lines.iter().all(|line| line.len() == width));
+        let rect = Rect::from_exclusive_ranges(0..width as i32, 0..height as i32);
         Matrix {
-            rect: Rect::from_exclusive_ranges(0..width as i32, 0..height as i32),
+            rect,
             values: lines.iter().flat_map(|line| line.bytes()).collect(),
         }
     }
 }
 
+// The error returned when Matrix::parse_with encounters a character it cannot map to a cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMatrixError {
+    pub pos: Point,
+    pub c: char,
+}
+
+impl Display for ParseMatrixError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "unexpected character {:?} at {}", self.c, self.pos)
+    }
+}
+
+impl std::error::Error for ParseMatrixError {}
+
+impl Matrix<u8> {
+    // Like collecting lines into a Matrix, but lines may differ in length; short lines are padded
+    // with fill. The first character of the first line will be at origin.
+    // Works on bytes, so multi-byte UTF-8 characters take up several cells, as when collecting.
+    pub fn parse(text: &str, origin: Point, fill: u8) -> Matrix<u8> {
+        let lines: Vec<&[u8]> = text.lines().map(str::as_bytes).collect();
+        let (rect, cells) = ragged_cells(&lines, origin, fill);
+        Matrix { rect, values: cells.map(|(_, b)| b).collect() }
+    }
+}
+
+// The bounding rect of lines laid out from origin, and each of its cells in reading order, with
+// short lines padded with fill.
+fn ragged_cells<'a, L, C>(lines: &'a [L], origin: Point, fill: C) -> (Rect, impl Iterator<Item = (Point, C)> + 'a)
+    where L: AsRef<[C]>, C: Copy + 'a
+{
+    let width = lines.iter().map(|line| line.as_ref().len()).max().unwrap_or(0);
+    let rect = Rect::from_exclusive_ranges(origin.x .. origin.x + width as i32, origin.y .. origin.y + lines.len() as i32);
+    let cells = rect.iter().map(move |pos| {
+        let offset = pos - origin;
+        (pos, lines[offset.y as usize].as_ref().get(offset.x as usize).cloned().unwrap_or(fill))
+    });
+    (rect, cells)
+}
+
+impl<T> Matrix<T> {
+    // Parses text into a Matrix of any cell type by mapping each character through f. Ragged lines
+    // are padded with the fill character, which f must accept too.
+    pub fn parse_with<F>(text: &str, origin: Point, fill: char, f: F) -> Result<Matrix<T>, ParseMatrixError>
+        where F: Fn(char) -> Option<T>
+    {
+        let lines: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        let (rect, cells) = ragged_cells(&lines, origin, fill);
+        let values = cells
+            .map(|(pos, c)| f(c).ok_or(ParseMatrixError { pos, c }))
+            .collect::<Result<Vec<T>, ParseMatrixError>>()?;
+        Ok(Matrix { rect, values })
+    }
+
+    // Finds the cells for which f returns a marker, in reading order.
+    pub fn find_markers<M, F>(&self, f: F) -> Vec<(Point, M)>
+        where F: Fn(&T) -> Option<M>
+    {
+        self.coords().filter_map(|pos| f(&self[pos]).map(|marker| (pos, marker))).collect()
+    }
+
+    // Like find_markers, but f also returns the tile that is underneath the marker, which replaces
+    // it in the matrix. Useful for taking units or carts off the map.
+    pub fn extract_markers<M, F>(&mut self, f: F) -> Vec<(Point, M)>
+        where F: Fn(&T) -> Option<(M, T)>
+    {
+        let mut markers = vec![];
+        for pos in self.rect.iter() {
+            if let Some((marker, tile)) = f(&self[pos]) {
+                let index = self.index_of(pos);
+                self.values[index] = tile;
+                markers.push((pos, marker));
+            }
+        }
+        markers
+    }
+}
+
+#[test]
+fn test_matrix_parse() {
+    let mat = Matrix::parse("#.\n\n###\n", Point::new(-1, 5), b' ');
+    assert_eq!(mat.rect(), &Rect::from_inclusive_ranges(-1 ..= 1, 5 ..= 7));
+    assert_eq!(mat.to_string(), "#. \n   \n###");
+    assert_eq!(mat[Point::new(-1, 5)], b'#');
+    assert!(Matrix::parse("", Point::origin(), b' ').rect().is_empty());
+    // Non-ASCII characters keep their UTF-8 bytes.
+    assert_eq!(Matrix::parse("é\n", Point::origin(), b' ').as_slice(), "é".as_bytes());
+}
+
+#[test]
+fn test_matrix_parse_with() {
+    let mat = Matrix::parse_with("#.\n.", Point::origin(), '.', |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    });
+    assert_eq!(mat.unwrap().as_slice(), &[true, false, false, false]);
+    let err = Matrix::parse_with("#.\n.x", Point::new(1, 1), '.', |c| if c == '#' || c == '.' { Some(c) } else { None }).err();
+    assert_eq!(err, Some(ParseMatrixError { pos: Point::new(2, 2), c: 'x' }));
+    assert_eq!(err.unwrap().to_string(), "unexpected character 'x' at 2,2");
+}
+
+#[test]
+fn test_matrix_markers() {
+    let mut mat = Matrix::parse("#E.\n.G#", Point::origin(), b'.');
+    let is_unit = |&c: &u8| if c == b'E' || c == b'G' { Some(c as char) } else { None };
+    assert_eq!(mat.find_markers(is_unit), vec![(Point::new(1, 0), 'E'), (Point::new(1, 1), 'G')]);
+    let units = mat.extract_markers(|&c| is_unit(&c).map(|unit| (unit, b'.')));
+    assert_eq!(units, vec![(Point::new(1, 0), 'E'), (Point::new(1, 1), 'G')]);
+    assert_eq!(mat.to_string(), "#..\n..#");
+}
+
 impl Display for Matrix<u8> {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         for y in self.rect().y_range() {
@@ -451,3 +2040,161 @@ impl Display for Matrix<u8> {
         Ok(())
     }
 }
+
+// A 2D grid that only stores the cells that have been set, so it can grow in any direction. It
+// keeps track of the bounding rect of all cells that have been set.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SparseGrid<T> {
+    rect: Rect,
+    values: HashMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid { rect: Rect::empty(), values: HashMap::new() }
+    }
+
+    // Copies all cells of the matrix that are not equal to fill.
+    pub fn from_matrix(matrix: &Matrix<T>, fill: &T) -> SparseGrid<T>
+        where T: Clone + PartialEq
+    {
+        let mut grid = SparseGrid::new();
+        for point in matrix.coords() {
+            if matrix[point] != *fill {
+                grid.insert(point, matrix[point].clone());
+            }
+        }
+        grid
+    }
+
+    // The bounding rect of all cells that are set.
+    pub fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.values.contains_key(&point)
+    }
+
+    // The coordinates of all cells that are set, in arbitrary order.
+    pub fn coords(&self) -> impl Iterator<Item=Point> + '_ {
+        self.values.keys().cloned()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(Point, &T)> {
+        self.values.iter().map(|(&point, value)| (point, value))
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.values.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.values.get_mut(&point)
+    }
+
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.rect = Rect::bounding_rects(&self.rect, &Rect::from_inclusive_ranges(point.x ..= point.x, point.y ..= point.y));
+        self.values.insert(point, value)
+    }
+
+    pub fn get_or_insert_with<F>(&mut self, point: Point, f: F) -> &mut T
+        where F: FnOnce() -> T
+    {
+        if !self.values.contains_key(&point) {
+            self.insert(point, f());
+        }
+        self.values.get_mut(&point).unwrap()
+    }
+
+    // Removes a cell, shrinking the bounding rect if the cell was on its border.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.values.remove(&point)?;
+        if point.x == self.rect.x_min() || point.x == self.rect.x_max() || point.y == self.rect.y_min() || point.y == self.rect.y_max() {
+            self.rect = self.values.keys().collect();
+            if self.values.is_empty() {
+                self.rect = Rect::empty();
+            }
+        }
+        Some(value)
+    }
+
+    // Converts to a dense Matrix covering the bounding rect, with unset cells set to fill.
+    pub fn to_matrix(&self, fill: T) -> Matrix<T>
+        where T: Clone
+    {
+        let mut matrix = Matrix::new(&self.rect, fill);
+        for (&point, value) in &self.values {
+            matrix[point] = value.clone();
+        }
+        matrix
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid::new()
+    }
+}
+
+impl<T> ops::Index<Point> for SparseGrid<T> {
+    type Output = T;
+    fn index(&self, point: Point) -> &T {
+        self.values.get(&point).unwrap_or_else(|| panic!("{} not set", point))
+    }
+}
+
+impl<T> ops::IndexMut<Point> for SparseGrid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.values.get_mut(&point).unwrap_or_else(|| panic!("{} not set", point))
+    }
+}
+
+// Renders like Matrix<u8>, with unset cells shown as spaces.
+impl Display for SparseGrid<u8> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_matrix(b' '))
+    }
+}
+
+#[test]
+fn test_sparse_grid() {
+    let mut grid = SparseGrid::new();
+    assert!(grid.rect().is_empty());
+    assert_eq!(grid.insert(Point::new(-2, 1), b'a'), None);
+    assert_eq!(grid.insert(Point::new(1, 3), b'b'), None);
+    assert_eq!(grid.insert(Point::new(1, 3), b'c'), Some(b'b'));
+    assert_eq!(grid.rect(), &Rect::from_inclusive_ranges(-2 ..= 1, 1 ..= 3));
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid[Point::new(1, 3)], b'c');
+    assert_eq!(grid.get(Point::new(0, 0)), None);
+    *grid.get_or_insert_with(Point::new(0, 0), || b'x') += 1;
+    assert_eq!(grid[Point::new(0, 0)], b'y');
+    assert_eq!(grid.rect(), &Rect::from_inclusive_ranges(-2 ..= 1, 0 ..= 3));
+    assert_eq!(grid.to_string(), "  y \na   \n    \n   c");
+
+    assert_eq!(grid.remove(Point::new(1, 3)), Some(b'c'));
+    assert_eq!(grid.rect(), &Rect::from_inclusive_ranges(-2 ..= 0, 0 ..= 1));
+    assert_eq!(grid.remove(Point::new(1, 3)), None);
+    grid.remove(Point::new(0, 0));
+    grid.remove(Point::new(-2, 1));
+    assert!(grid.is_empty());
+    assert!(grid.rect().is_empty());
+}
+
+#[test]
+fn test_sparse_grid_matrix_conversion() {
+    let matrix: Matrix<u8> = vec!["#..", "..#"].into_iter().collect();
+    let grid = SparseGrid::from_matrix(&matrix, &b'.');
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid.rect(), &Rect::from_inclusive_ranges(0 ..= 2, 0 ..= 1));
+    assert!(grid.to_matrix(b'.') == matrix);
+}
@@ -0,0 +1,256 @@
+use crate::disjoint_set::DisjointSet;
+use fixedbitset::FixedBitSet;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+// An undirected graph without self-loops on the vertices 0..n, stored as an adjacency bitset per
+// vertex.
+#[derive(Clone, Debug)]
+pub struct Graph {
+    adjacency: Vec<FixedBitSet>,
+}
+
+impl Graph {
+    // Creates a graph with n vertices and no edges.
+    pub fn new(n: usize) -> Graph {
+        Graph { adjacency: vec![FixedBitSet::with_capacity(n); n] }
+    }
+
+    // Creates a graph with n vertices, with an edge between i and j wherever has_edge(i, j) returns
+    // true. It is only called with i < j.
+    pub fn from_fn<F: FnMut(usize, usize) -> bool>(n: usize, mut has_edge: F) -> Graph {
+        let mut graph = Graph::new(n);
+        for i in 0..n {
+            for j in i + 1..n {
+                if has_edge(i, j) {
+                    graph.add_edge(i, j);
+                }
+            }
+        }
+        graph
+    }
+
+    // The number of vertices.
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize) {
+        assert!(a != b, "self-loop on vertex {}", a);
+        self.adjacency[a].insert(b);
+        self.adjacency[b].insert(a);
+    }
+
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.adjacency[a].contains(b)
+    }
+
+    pub fn neighbors(&self, vertex: usize) -> &FixedBitSet {
+        &self.adjacency[vertex]
+    }
+
+    pub fn degree(&self, vertex: usize) -> usize {
+        self.adjacency[vertex].count_ones(..)
+    }
+
+    // All maximal cliques, i.e. cliques that cannot be extended by another vertex. Each clique is
+    // sorted.
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques = vec![];
+        self.bron_kerbosch(&mut vec![], self.all_vertices(), FixedBitSet::with_capacity(self.len()), false, &mut cliques);
+        cliques
+    }
+
+    // All cliques of the largest possible size. Each clique is sorted.
+    pub fn maximum_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques = vec![];
+        self.bron_kerbosch(&mut vec![], self.all_vertices(), FixedBitSet::with_capacity(self.len()), true, &mut cliques);
+        cliques
+    }
+
+    // The vertex sets of the connected components, each sorted, ordered by their smallest vertex.
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let mut sets = DisjointSet::new(self.len());
+        for (a, neighbors) in self.adjacency.iter().enumerate() {
+            for b in neighbors.ones() {
+                sets.union(a, b);
+            }
+        }
+        sets.sets()
+    }
+
+    fn all_vertices(&self) -> FixedBitSet {
+        let mut vertices = FixedBitSet::with_capacity(self.len());
+        vertices.insert_range(..);
+        vertices
+    }
+
+    /*
+    https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm
+
+    BronKerbosch2(R, P, X):
+       if P and X are both empty:
+           report R as a maximal clique
+       choose a pivot vertex u in P ⋃ X
+       for each vertex v in P \ N(u):
+           BronKerbosch2(R ⋃ {v}, P ⋂ N(v), X ⋂ N(v))
+           P := P \ {v}
+           X := X ⋃ {v}
+
+    If maximum_only is set, cliques that are smaller than the largest found so far are dropped,
+    and branches that cannot beat it are not explored.
+     */
+    fn bron_kerbosch(&self, r: &mut Vec<usize>, mut p: FixedBitSet, mut x: FixedBitSet, maximum_only: bool, out: &mut Vec<Vec<usize>>) {
+        let p_size = p.count_ones(..);
+        let largest_size = out.first().map_or(0, Vec::len);
+        if maximum_only && r.len() + p_size < largest_size {
+            return;
+        }
+
+        if p_size == 0 && x.count_ones(..) == 0 {
+            if maximum_only && r.len() > largest_size {
+                out.clear();
+            }
+            let mut clique = r.clone();
+            clique.sort_unstable();
+            out.push(clique);
+            return;
+        }
+
+        // Any maximal clique contains the pivot or one of its non-neighbors, so we only need to
+        // branch on those. Picking the pivot with the most neighbors in P minimizes the branching.
+        let pivot = p.union(&x)
+            .max_by_key(|&u| count_common(&p, &self.adjacency[u]))
+            .unwrap();
+        let candidates: Vec<usize> = p.difference(&self.adjacency[pivot]).collect();
+        for v in candidates {
+            let neighbors = &self.adjacency[v];
+            r.push(v);
+            self.bron_kerbosch(r, &p & neighbors, &x & neighbors, maximum_only, out);
+            r.pop();
+            p.set(v, false);
+            x.insert(v);
+        }
+    }
+}
+
+// The size of the intersection, counted a word at a time.
+fn count_common(a: &FixedBitSet, b: &FixedBitSet) -> usize {
+    a.as_slice().iter().zip(b.as_slice()).map(|(a, b)| (a & b).count_ones() as usize).sum()
+}
+
+// Kahn's algorithm over a directed graph given by its edges (before, after). Hands out the nodes
+// whose predecessors have all been completed, smallest first. Taking and completing nodes are
+// separate steps, so several nodes can be in progress at once.
+pub struct TopologicalSort<T> {
+    ready: BTreeSet<T>,
+    num_predecessors: HashMap<T, usize>,
+    successors: HashMap<T, Vec<T>>,
+}
+
+impl<T: Ord + Hash + Clone> TopologicalSort<T> {
+    pub fn new<I: IntoIterator<Item = (T, T)>>(edges: I) -> TopologicalSort<T> {
+        let mut num_predecessors = HashMap::new();
+        let mut successors: HashMap<T, Vec<T>> = HashMap::new();
+        for (before, after) in edges {
+            num_predecessors.entry(before.clone()).or_insert(0);
+            *num_predecessors.entry(after.clone()).or_insert(0) += 1;
+            successors.entry(before).or_default().push(after);
+        }
+        let ready = num_predecessors.iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(node, _)| node.clone())
+            .collect();
+        TopologicalSort { ready, num_predecessors, successors }
+    }
+
+    // Takes the smallest node whose predecessors have all been completed.
+    pub fn take_ready(&mut self) -> Option<T> {
+        let node = self.ready.iter().next()?.clone();
+        self.ready.remove(&node);
+        Some(node)
+    }
+
+    // Marks a node that was taken as completed, which may make its successors ready.
+    pub fn complete(&mut self, node: &T) {
+        for successor in self.successors.remove(node).into_iter().flatten() {
+            let count = self.num_predecessors.get_mut(&successor).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.ready.insert(successor);
+            }
+        }
+    }
+}
+
+// Orders all nodes so that each edge (before, after) points forward, breaking ties by taking the
+// smallest available node first. Returns None if the edges contain a cycle.
+pub fn topological_sort<T, I>(edges: I) -> Option<Vec<T>>
+    where T: Ord + Hash + Clone, I: IntoIterator<Item = (T, T)>
+{
+    let mut sort = TopologicalSort::new(edges);
+    let mut order = Vec::with_capacity(sort.num_predecessors.len());
+    while let Some(node) = sort.take_ready() {
+        sort.complete(&node);
+        order.push(node);
+    }
+    if order.len() == sort.num_predecessors.len() { Some(order) } else { None }
+}
+
+#[cfg(test)]
+fn example_graph() -> Graph {
+    // Two triangles 0-1-2 and 1-2-3 sharing an edge, a square 4-5-6-7, and an isolated vertex 8.
+    let mut graph = Graph::new(9);
+    for &(a, b) in &[(0, 1), (0, 2), (1, 2), (1, 3), (2, 3), (4, 5), (5, 6), (6, 7), (7, 4)] {
+        graph.add_edge(a, b);
+    }
+    graph
+}
+
+#[test]
+fn test_graph() {
+    let graph = example_graph();
+    assert_eq!(graph.len(), 9);
+    assert!(graph.has_edge(3, 1));
+    assert!(!graph.has_edge(0, 3));
+    assert_eq!(graph.neighbors(2).ones().collect::<Vec<_>>(), vec![0, 1, 3]);
+    assert_eq!(graph.degree(8), 0);
+    let from_fn = Graph::from_fn(4, |i, j| j == i + 1);
+    assert_eq!(from_fn.neighbors(1).ones().collect::<Vec<_>>(), vec![0, 2]);
+}
+
+#[test]
+fn test_cliques() {
+    let graph = example_graph();
+    let mut maximal = graph.maximal_cliques();
+    maximal.sort();
+    assert_eq!(maximal, vec![vec![0, 1, 2], vec![1, 2, 3], vec![4, 5], vec![4, 7], vec![5, 6], vec![6, 7], vec![8]]);
+    let mut maximum = graph.maximum_cliques();
+    maximum.sort();
+    assert_eq!(maximum, vec![vec![0, 1, 2], vec![1, 2, 3]]);
+    assert_eq!(Graph::new(0).maximum_cliques(), vec![Vec::<usize>::new()]);
+}
+
+#[test]
+fn test_connected_components() {
+    assert_eq!(example_graph().connected_components(), vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8]]);
+}
+
+#[test]
+fn test_topological_sort() {
+    let edges = vec![('C', 'A'), ('C', 'F'), ('A', 'B'), ('A', 'D'), ('B', 'E'), ('D', 'E'), ('F', 'E')];
+    assert_eq!(topological_sort(edges.clone()), Some(vec!['C', 'A', 'B', 'D', 'F', 'E']));
+
+    let mut sort = TopologicalSort::new(edges);
+    assert_eq!(sort.take_ready(), Some('C'));
+    assert_eq!(sort.take_ready(), None);
+    sort.complete(&'C');
+    assert_eq!(sort.take_ready(), Some('A'));
+    assert_eq!(sort.take_ready(), Some('F'));
+
+    assert_eq!(topological_sort(vec![(1, 2), (2, 3), (3, 2)]), None);
+}
@@ -1,38 +1,362 @@
-use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::fs;
 use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
-pub fn get_input(year: u32, day: u32) -> String {
-    let input_file_name = input_file_name(year, day);
-    fs::read_to_string(&input_file_name)
-        .or_else(|_err| -> Result<String, Box<dyn Error>> {
-            println!("Input file {} could not be read, fetching...", input_file_name);
-            let contents = fetch_input(year, day)?;
-            fs::write(&input_file_name, &contents)?;
-            Ok(contents)
-        })
-        .unwrap()
+#[derive(Debug)]
+pub enum InputError {
+    // The input is not in the cache, and we are not allowed to fetch it.
+    MissingCache(PathBuf),
+    // The session cookie file could not be read or is empty.
+    MissingCookie(PathBuf),
+    // The server responded, but not with a success status.
+    HttpStatus(reqwest::StatusCode),
+    // The request could not be sent or the response could not be read.
+    Network(reqwest::Error),
+    // A local file could not be read.
+    Read(PathBuf, io::Error),
+    // The fetched input could not be written to the cache.
+    Write(PathBuf, io::Error),
 }
 
-fn input_file_name(_year: u32, day: u32) -> String {
-    format!("input/{:02}.in", day)
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            InputError::MissingCache(path) => write!(f, "{} does not exist and fetching is disabled", path.display()),
+            InputError::MissingCookie(path) => write!(f, "session cookie could not be read from {}", path.display()),
+            InputError::HttpStatus(status) => write!(f, "server responded with {}", status),
+            InputError::Network(err) => write!(f, "request failed: {}", err),
+            InputError::Read(path, err) => write!(f, "{} could not be read: {}", path.display(), err),
+            InputError::Write(path, err) => write!(f, "{} could not be written: {}", path.display(), err),
+        }
+    }
 }
 
-fn load_session_cookie() -> Result<String, io::Error> {
-    fs::read_to_string(".session_cookie")
-        .map(|s| s.trim().to_string())
+impl std::error::Error for InputError {}
+
+// Before multiple years were supported, all files lived directly in input/ and were for 2018.
+pub const LEGACY_YEAR: u32 = 2018;
+
+// Returns the paths where a per-day file for the given year may be found, in order of preference.
+// Files live in a subdirectory per year, but for the legacy year they may also be directly in dir.
+pub fn year_file_names(dir: &Path, year: u32, file_name: &str) -> Vec<PathBuf> {
+    let mut file_names = vec![dir.join(year.to_string()).join(file_name)];
+    if year == LEGACY_YEAR {
+        file_names.push(dir.join(file_name));
+    }
+    file_names
+}
+
+// Where to find cached inputs, and how to fetch them if they are not cached.
+#[derive(Clone, Debug)]
+pub struct InputSource {
+    pub base_url: String,
+    pub cache_dir: PathBuf,
+    pub session_cookie_file: PathBuf,
+    pub offline: bool,
+}
+
+impl Default for InputSource {
+    fn default() -> InputSource {
+        InputSource {
+            base_url: "https://adventofcode.com".to_string(),
+            cache_dir: PathBuf::from("input"),
+            session_cookie_file: PathBuf::from(".session_cookie"),
+            offline: false,
+        }
+    }
+}
+
+impl InputSource {
+    // Returns the cached input, fetching and caching it first if needed.
+    pub fn get_input(&self, year: u32, day: u32) -> Result<String, InputError> {
+        for input_file_name in self.input_file_names(year, day) {
+            match fs::read_to_string(&input_file_name) {
+                Ok(contents) => return Ok(contents),
+                // Only a missing file is a cache miss; anything else would be overwritten by a fetch.
+                Err(ref err) if is_not_found(err) => continue,
+                Err(err) => return Err(InputError::Read(input_file_name, err)),
+            }
+        }
+        if self.offline {
+            return Err(InputError::MissingCache(self.input_file_name(year, day)));
+        }
+        eprintln!("Input file {} could not be read, fetching...", self.input_file_name(year, day).display());
+        self.fetch_and_cache(year, day)
+    }
+
+    // Returns the path of the cached input, if it exists in any of the supported locations.
+    pub fn cached_input_file_name(&self, year: u32, day: u32) -> Option<PathBuf> {
+        self.input_file_names(year, day).into_iter().find(|path| path.is_file())
+    }
+
+    // Fetches the input and writes it to the cache, overwriting any existing file.
+    pub fn fetch_and_cache(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let input_file_name = self.input_file_name(year, day);
+        let contents = normalize_trailing_newline(&self.fetch_input(year, day)?);
+        fs::create_dir_all(input_file_name.parent().unwrap())
+            .and_then(|_| fs::write(&input_file_name, &contents))
+            .map_err(|err| InputError::Write(input_file_name, err))?;
+        Ok(contents)
+    }
+
+    // The path where new inputs are cached.
+    pub fn input_file_name(&self, year: u32, day: u32) -> PathBuf {
+        self.input_file_names(year, day).remove(0)
+    }
+
+    fn input_file_names(&self, year: u32, day: u32) -> Vec<PathBuf> {
+        year_file_names(&self.cache_dir, year, &format!("{:02}.in", day))
+    }
+
+    fn fetch_input(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let session_cookie = load_session_cookie(&self.session_cookie_file)?;
+        let mut response = reqwest::Client::new()
+            .get(&url)
+            .header(reqwest::header::COOKIE, format!("session={}", session_cookie))
+            .send()
+            .map_err(InputError::Network)?;
+        if !response.status().is_success() {
+            return Err(InputError::HttpStatus(response.status()));
+        }
+        response.text().map_err(InputError::Network)
+    }
+}
+
+// Whether a read failed because the file does not exist, which includes the case where part of
+// its directory path is a regular file.
+fn is_not_found(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::NotFound | io::ErrorKind::NotADirectory)
+}
+
+// Makes sure the input ends in exactly one newline, unless it is empty.
+pub fn normalize_trailing_newline(input: &str) -> String {
+    let trimmed = input.trim_end_matches(['\n', '\r']);
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+#[test]
+fn test_normalize_trailing_newline() {
+    assert_eq!(normalize_trailing_newline(""), "");
+    assert_eq!(normalize_trailing_newline("\n"), "");
+    assert_eq!(normalize_trailing_newline("a\nb"), "a\nb\n");
+    assert_eq!(normalize_trailing_newline("a\nb\n"), "a\nb\n");
+    assert_eq!(normalize_trailing_newline("a\nb\r\n\n\n"), "a\nb\n");
+    assert_eq!(normalize_trailing_newline("  |  \n"), "  |  \n");
+}
+
+// Returns the (year, day) of the puzzle released most recently, if it is December 1 through 25.
+// Puzzles unlock at midnight in the US Eastern time zone (UTC-5).
+pub fn puzzle_today() -> Option<(u32, u32)> {
+    let seconds = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    puzzle_on((seconds - 5 * 60 * 60) / (24 * 60 * 60))
 }
 
-fn fetch_input(year: u32, day: u32) -> Result<String, Box<dyn Error>> {
-    let url = format!("https://adventofcode.com/{}/day/{}/input", year, day);
-    let client = reqwest::Client::new();
-    let session_cookie = load_session_cookie()?;
-    client.get(&url)
-        .header(reqwest::header::COOKIE, format!("session={}", session_cookie))
-        .send()
-        .expect("request failed")
-        .error_for_status()?
-        .text()
-        .map_err(From::from)
+// Converts days since the Unix epoch to the (year, day) of a puzzle, if it is December 1 through
+// 25. Uses the days-to-civil algorithm from http://howardhinnant.github.io/date_algorithms.html.
+fn puzzle_on(days_since_epoch: u64) -> Option<(u32, u32)> {
+    let z = days_since_epoch + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    if month == 12 && day <= 25 {
+        Some((year as u32, day as u32))
+    } else {
+        None
+    }
 }
 
+#[test]
+fn test_puzzle_on() {
+    // 2018-12-01 is day 17866 since the epoch.
+    assert_eq!(puzzle_on(17866), Some((2018, 1)));
+    assert_eq!(puzzle_on(17866 + 24), Some((2018, 25)));
+    assert_eq!(puzzle_on(17866 + 25), None);
+    assert_eq!(puzzle_on(17866 - 1), None);
+    assert_eq!(puzzle_on(17866 + 365), Some((2019, 1)));
+}
+
+// Reads input from an explicitly given file, or from stdin if the path is "-". Never touches the
+// cache or the network.
+pub fn read_input(path: &str) -> Result<String, io::Error> {
+    if path == "-" {
+        let mut contents = String::new();
+        io::stdin().lock().read_to_string(&mut contents)?;
+        Ok(contents)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+pub fn load_session_cookie(path: &Path) -> Result<String, InputError> {
+    match fs::read_to_string(path) {
+        Ok(ref contents) if !contents.trim().is_empty() => Ok(contents.trim().to_string()),
+        _ => Err(InputError::MissingCookie(path.to_path_buf())),
+    }
+}
+
+// Serves a single canned HTTP response on a local port, and returns the base URL to reach it
+// along with a handle that yields the request it received, including any body.
+#[cfg(test)]
+pub fn serve_once(status: &str, body: &str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::Write;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body);
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        let mut buffer = [0; 1024];
+        loop {
+            if let Some(header_end) = request.find("\r\n\r\n") {
+                let content_length = request[..header_end].lines()
+                    .filter_map(|line| {
+                        let (name, value) = line.split_at(line.find(':')?);
+                        if name.eq_ignore_ascii_case("content-length") { value[1..].trim().parse::<usize>().ok() } else { None }
+                    })
+                    .next()
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+            let n = stream.read(&mut buffer).unwrap();
+            if n == 0 {
+                break;
+            }
+            request.push_str(std::str::from_utf8(&buffer[..n]).unwrap());
+        }
+        stream.write_all(response.as_bytes()).unwrap();
+        request
+    });
+    (base_url, handle)
+}
+
+// Creates an empty scratch directory containing a session cookie file.
+#[cfg(test)]
+pub fn test_source(name: &str, base_url: &str) -> InputSource {
+    let dir = std::env::temp_dir().join(format!("aoc-input-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("cookie"), "secret\n").unwrap();
+    InputSource {
+        base_url: base_url.to_string(),
+        cache_dir: dir.clone(),
+        session_cookie_file: dir.join("cookie"),
+        offline: false,
+    }
+}
+
+#[test]
+fn test_get_input_fetches_and_caches() {
+    let (base_url, server) = serve_once("200 OK", "+1\n-2\n");
+    let source = test_source("fetch", &base_url);
+    assert_eq!(source.get_input(2018, 1).unwrap(), "+1\n-2\n");
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /2018/day/1/input "));
+    assert!(request.to_lowercase().contains("cookie: session=secret\r\n"));
+    assert_eq!(fs::read_to_string(source.cache_dir.join("2018/01.in")).unwrap(), "+1\n-2\n");
+
+    // The second time, it comes from the cache even though the server is gone.
+    assert_eq!(source.get_input(2018, 1).unwrap(), "+1\n-2\n");
+}
+
+#[test]
+fn test_get_input_http_status() {
+    let (base_url, server) = serve_once("404 Not Found", "");
+    let source = test_source("status", &base_url);
+    match source.get_input(2018, 2) {
+        Err(InputError::HttpStatus(status)) => assert_eq!(status.as_u16(), 404),
+        other => panic!("unexpected result {:?}", other),
+    }
+    server.join().unwrap();
+    assert!(!source.cache_dir.join("2018/02.in").exists());
+}
+
+#[test]
+fn test_get_input_network_failure() {
+    // Bind and immediately drop a listener to find a port that nothing is listening on.
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let source = test_source("network", &format!("http://127.0.0.1:{}", port));
+    match source.get_input(2018, 3) {
+        Err(InputError::Network(_)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_input_missing_cookie() {
+    let mut source = test_source("cookie", "http://127.0.0.1:1");
+    source.session_cookie_file = source.cache_dir.join("no_such_cookie");
+    match source.get_input(2018, 4) {
+        Err(InputError::MissingCookie(_)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_get_input_offline() {
+    let mut source = test_source("offline", "http://127.0.0.1:1");
+    source.offline = true;
+    match source.get_input(2018, 5) {
+        Err(InputError::MissingCache(_)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    fs::create_dir(source.cache_dir.join("2019")).unwrap();
+    fs::write(source.cache_dir.join("2019/05.in"), "cached").unwrap();
+    assert_eq!(source.get_input(2019, 5).unwrap(), "cached");
+}
+
+#[test]
+fn test_get_input_legacy_layout() {
+    let mut source = test_source("legacy", "http://127.0.0.1:1");
+    source.offline = true;
+    fs::write(source.cache_dir.join("07.in"), "flat").unwrap();
+    assert_eq!(source.get_input(2018, 7).unwrap(), "flat");
+    // Only 2018 inputs can be in the flat layout.
+    match source.get_input(2019, 7) {
+        Err(InputError::MissingCache(_)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    // The per-year layout takes precedence.
+    fs::create_dir(source.cache_dir.join("2018")).unwrap();
+    fs::write(source.cache_dir.join("2018/07.in"), "per year").unwrap();
+    assert_eq!(source.get_input(2018, 7).unwrap(), "per year");
+}
+
+#[test]
+fn test_get_input_write_failure() {
+    let (base_url, server) = serve_once("200 OK", "ok");
+    let mut source = test_source("write", &base_url);
+    // The year directory can't be created inside a regular file.
+    source.cache_dir = source.cache_dir.join("cookie");
+    match source.get_input(2018, 6) {
+        Err(InputError::Write(_, _)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn test_get_input_read_failure() {
+    let source = test_source("read", "http://127.0.0.1:1");
+    fs::create_dir(source.cache_dir.join("2018")).unwrap();
+    fs::write(source.cache_dir.join("2018/07.in"), b"\xff\xfe").unwrap();
+    // A cache file that exists but can't be read is an error, not a reason to fetch it again.
+    match source.get_input(2018, 7) {
+        Err(InputError::Read(_, _)) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(fs::read(source.cache_dir.join("2018/07.in")).unwrap(), b"\xff\xfe");
+}
@@ -1,27 +1,243 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::env;
 use std::fmt::Display;
+use std::path::Path;
+use std::process;
 
+pub mod cycle;
+pub mod disjoint_set;
 pub mod geom;
+pub mod graph;
+pub mod input;
+pub mod search;
 pub mod vm;
 
-mod input;
+mod answers;
+mod bench;
+mod error;
+mod options;
+mod report;
+mod submit;
 
+use crate::answers::Answers;
+use crate::bench::Stats;
+use crate::input::InputSource;
+use crate::report::Report;
+use crate::submit::Verdict;
+
+pub use crate::error::{Error, parse_lines};
+pub use crate::options::Options;
+
+type Part = Box<dyn Fn(&str) -> Result<String, Error>>;
+
+// Both parts of a single day's puzzle, with their result types erased so that solutions for
+// different days can be stored in one table.
+pub struct Solution {
+    year: u32,
+    day: u32,
+    parts: [Part; 2],
+}
+
+impl Solution {
+    pub fn new<P1, P2, R1, R2>(year: u32, day: u32, part1: P1, part2: P2) -> Solution
+        where P1: Fn(&str) -> R1 + 'static, P2: Fn(&str) -> R2 + 'static, R1: Display, R2: Display
+    {
+        Solution::try_new(year, day, move |input| Ok::<_, Error>(part1(input)), move |input| Ok::<_, Error>(part2(input)))
+    }
+
+    // Like new, for parts that can fail, e.g. on malformed input.
+    pub fn try_new<P1, P2, R1, R2, E1, E2>(year: u32, day: u32, part1: P1, part2: P2) -> Solution
+        where P1: Fn(&str) -> Result<R1, E1> + 'static, P2: Fn(&str) -> Result<R2, E2> + 'static,
+              R1: Display, R2: Display, E1: Into<Error>, E2: Into<Error>
+    {
+        Solution {
+            year,
+            day,
+            parts: [
+                Box::new(move |input| part1(input).map(|answer| answer.to_string()).map_err(Into::into)),
+                Box::new(move |input| part2(input).map(|answer| answer.to_string()).map_err(Into::into)),
+            ],
+        }
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+}
+
+// Runs a solution for 2018, the year this crate was written for.
 pub fn main<P1, P2, R1, R2>(part1: P1, part2: P2)
-    where P1: Fn(&str) -> R1, P2: Fn(&str) -> R2, R1: Display, R2: Display
+    where P1: Fn(&str) -> R1 + 'static, P2: Fn(&str) -> R2 + 'static, R1: Display, R2: Display
+{
+    main_for_year(2018, part1, part2);
+}
+
+pub fn main_for_year<P1, P2, R1, R2>(year: u32, part1: P1, part2: P2)
+    where P1: Fn(&str) -> R1 + 'static, P2: Fn(&str) -> R2 + 'static, R1: Display, R2: Display
+{
+    run_single_day(|day| Solution::new(year, day, part1, part2));
+}
+
+// Like main, for parts that return a Result.
+pub fn try_main<P1, P2, R1, R2, E1, E2>(part1: P1, part2: P2)
+    where P1: Fn(&str) -> Result<R1, E1> + 'static, P2: Fn(&str) -> Result<R2, E2> + 'static,
+          R1: Display, R2: Display, E1: Into<Error>, E2: Into<Error>
 {
-    let day = env::current_exe().unwrap().file_stem().unwrap().to_str().unwrap().parse::<u32>().unwrap();
-    let input = input::get_input(2018, day);
-    run(day, 1, part1, &input);
-    run(day, 2, part2, &input);
+    try_main_for_year(2018, part1, part2);
 }
 
-fn run<P, R>(day: u32, part: u32, func: P, input: &str)
-    where P: Fn(&str) -> R, R: Display
+pub fn try_main_for_year<P1, P2, R1, R2, E1, E2>(year: u32, part1: P1, part2: P2)
+    where P1: Fn(&str) -> Result<R1, E1> + 'static, P2: Fn(&str) -> Result<R2, E2> + 'static,
+          R1: Display, R2: Display, E1: Into<Error>, E2: Into<Error>
+{
+    run_single_day(|day| Solution::try_new(year, day, part1, part2));
+}
+
+fn run_single_day<F: FnOnce(u32) -> Solution>(solution: F) {
+    let options = parse_options();
+    let day = match day_from_exe_name() {
+        Some(day) if !options.includes_day(day) =>
+            exit_with_usage(&format!("This binary only solves day {}, which --day does not include", day)),
+        Some(day) => day,
+        None => options.single_day()
+            .unwrap_or_else(|| exit_with_usage("Could not infer the day from the binary name; pass --day N")),
+    };
+    run_solutions(&[solution(day)], &options);
+}
+
+// Parses the process arguments into Options, exiting with a usage message if they are invalid.
+pub fn parse_options() -> Options {
+    Options::parse(env::args().skip(1))
+        .unwrap_or_else(|err| exit_with_usage(&err))
+}
+
+pub fn exit_with_usage(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, Options::usage());
+    process::exit(2);
+}
+
+// Binaries are named after the day they solve, optionally followed by a suffix, e.g. "19b".
+fn day_from_exe_name() -> Option<u32> {
+    let exe = env::current_exe().ok()?;
+    let stem = exe.file_stem()?.to_str()?;
+    let digits: String = stem.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+pub fn run_solutions(solutions: &[Solution], options: &Options) {
+    let selected: Vec<&Solution> = solutions.iter()
+        .filter(|solution| options.includes_year(solution.year) && options.includes_day(solution.day))
+        .collect();
+    if selected.is_empty() {
+        exit_with_usage("No solutions match the selected year and days");
+    }
+    if options.input.is_some() && selected.len() > 1 {
+        exit_with_usage("--input can only be used when running a single day");
+    }
+    if options.submit && selected.len() > 1 {
+        exit_with_usage("submit can only be used for a single day");
+    }
+    let input_source = InputSource { offline: options.offline, ..InputSource::default() };
+    let mut failures = 0;
+    let mut bench_results = Vec::new();
+    for solution in selected {
+        let input = match &options.input {
+            Some(path) => Ok(input::read_input(path).unwrap_or_else(|err| {
+                eprintln!("Input file {} could not be read: {}", path, err);
+                process::exit(1);
+            })),
+            None => input_source.get_input(solution.year, solution.day)
+                .map_err(|err| Error::new(format!("input could not be loaded: {}", err))),
+        };
+        let answers = if options.check {
+            Answers::load(&input_source, solution.year, solution.day).unwrap_or_else(|err| {
+                eprintln!("Answers for {} day {} could not be read: {}", solution.year, solution.day, err);
+                process::exit(1);
+            })
+        } else {
+            Answers::default()
+        };
+        for (part, func) in (1..).zip(solution.parts.iter()) {
+            if !options.includes_part(part) {
+                continue;
+            }
+            let mut report = match &input {
+                Ok(input) => run_part(solution, part, func, input, options.bench),
+                Err(err) => Report {
+                    year: solution.year,
+                    day: solution.day,
+                    part,
+                    duration: Duration::default(),
+                    result: Err(err.clone()),
+                    check: None,
+                    stats: None,
+                },
+            };
+            if options.check {
+                if let Ok(output) = &report.result {
+                    report.check = Some(answers.check(part, output));
+                }
+            }
+            report.print(options.format);
+            if report.is_failure() {
+                failures += 1;
+            }
+            if let Some(stats) = &report.stats {
+                bench_results.push((solution.year, solution.day, part, stats.clone()));
+            }
+            if let (true, Ok(output)) = (options.submit, &report.result) {
+                match submit::submit(&input_source, solution.year, solution.day, part, output) {
+                    Ok(Verdict::Correct) => eprintln!("Submission result: {}", Verdict::Correct),
+                    Ok(verdict) => {
+                        eprintln!("Submission result: {}", verdict);
+                        failures += 1;
+                    }
+                    Err(err) => {
+                        eprintln!("Submission failed: {}", err);
+                        failures += 1;
+                    }
+                }
+            }
+        }
+    }
+    if let Some(path) = &options.csv {
+        if let Err(err) = bench::append_csv(Path::new(path), &bench_results) {
+            eprintln!("Benchmark results could not be written to {}: {}", path, err);
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        eprintln!("{} failure(s)", failures);
+        process::exit(1);
+    }
+}
+
+// Runs a single part. When benchmarking, the first run serves as the warm-up, and the reported
+// duration is the median of the runs after it.
+fn run_part(solution: &Solution, part: u32, func: &Part, input: &str, bench: Option<usize>) -> Report {
+    let (result, mut duration) = run(func, input);
+    let stats = match (&result, bench) {
+        (Ok(_), Some(runs)) => {
+            let durations: Vec<Duration> = (0..runs).map(|_| run(func, input).1).collect();
+            Some(Stats::from_durations(&durations))
+        }
+        _ => None,
+    };
+    if let Some(stats) = &stats {
+        duration = stats.median_duration();
+    }
+    Report { year: solution.year, day: solution.day, part, duration, result, check: None, stats }
+}
+
+fn run<P>(func: P, input: &str) -> (Result<String, Error>, Duration)
+    where P: Fn(&str) -> Result<String, Error>
 {
     let start = Instant::now();
     let output = func(input);
     let duration = start.elapsed();
-
-    println!("Answer to day {}, part {} ({}.{:03} s): {}", day, part, duration.as_secs(), duration.subsec_millis(), output);
+    (output, duration)
 }
@@ -0,0 +1,230 @@
+use std::ops::RangeInclusive;
+
+use crate::report::Format;
+
+const USAGE: &str = "\
+Arguments: [submit] [options]
+
+The submit command submits the answer for a single day and part, unless it is known to be wrong.
+
+Options:
+    --year YYYY   run only solutions for the given year
+    --day N       run only day N
+    --day A-B     run days A through B (inclusive)
+    --part N      run only part N (1 or 2)
+    --all         run all days
+    --input PATH  read the input from PATH instead of the cache, or from stdin if PATH is -
+    --check       compare answers against input/NN.ans and fail on any mismatch
+    --offline     never fetch inputs from the network
+    --bench N     after a warm-up run, run each part N times and report timing statistics
+    --csv PATH    append benchmark results to the CSV file at PATH
+    --format F    print results as text (the default) or json, one object per line";
+
+// Command line options shared by the per-day binaries and the multi-day runner.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    pub year: Option<u32>,
+    pub days: Option<RangeInclusive<u32>>,
+    pub part: Option<u32>,
+    pub all: bool,
+    pub input: Option<String>,
+    pub check: bool,
+    pub offline: bool,
+    pub submit: bool,
+    pub bench: Option<usize>,
+    pub csv: Option<String>,
+    pub format: Format,
+}
+
+impl Options {
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+
+    pub fn parse<I>(args: I) -> Result<Options, String>
+        where I: IntoIterator<Item = String>
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter().peekable();
+        if args.peek().map(String::as_str) == Some("submit") {
+            args.next();
+            options.submit = true;
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--year" => {
+                    let value = args.next().ok_or("--year requires a value")?;
+                    options.year = match value.parse::<u32>() {
+                        Ok(year) if year >= 2015 => Some(year),
+                        _ => return Err(format!("invalid year {:?}", value)),
+                    };
+                }
+                "--day" => {
+                    let value = args.next().ok_or("--day requires a value")?;
+                    options.days = Some(parse_day_range(&value)?);
+                }
+                "--part" => {
+                    let value = args.next().ok_or("--part requires a value")?;
+                    options.part = match value.parse::<u32>() {
+                        Ok(part @ 1..=2) => Some(part),
+                        _ => return Err(format!("invalid part {:?}, expected 1 or 2", value)),
+                    };
+                }
+                "--all" => {
+                    options.all = true;
+                }
+                "--input" => {
+                    options.input = Some(args.next().ok_or("--input requires a path")?);
+                }
+                "--check" => {
+                    options.check = true;
+                }
+                "--offline" => {
+                    options.offline = true;
+                }
+                "--bench" => {
+                    let value = args.next().ok_or("--bench requires a value")?;
+                    options.bench = match value.parse::<usize>() {
+                        Ok(runs) if runs > 0 => Some(runs),
+                        _ => return Err(format!("invalid number of runs {:?}", value)),
+                    };
+                }
+                "--csv" => {
+                    options.csv = Some(args.next().ok_or("--csv requires a path")?);
+                }
+                "--format" => {
+                    let value = args.next().ok_or("--format requires a value")?;
+                    options.format = match value.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        _ => return Err(format!("invalid format {:?}, expected text or json", value)),
+                    };
+                }
+                _ => return Err(format!("unknown argument {:?}", arg)),
+            }
+        }
+        if options.all && options.days.is_some() {
+            return Err("--all and --day cannot be combined".to_string());
+        }
+        if options.csv.is_some() && options.bench.is_none() {
+            return Err("--csv requires --bench".to_string());
+        }
+        if options.submit {
+            if options.part.is_none() || options.all {
+                return Err("submit requires a single --part".to_string());
+            }
+            if options.input.is_some() || options.offline {
+                return Err("submit cannot be combined with --input or --offline".to_string());
+            }
+        }
+        Ok(options)
+    }
+
+    // Returns the day if exactly one was selected.
+    pub fn single_day(&self) -> Option<u32> {
+        match &self.days {
+            Some(days) if days.start() == days.end() => Some(*days.start()),
+            _ => None,
+        }
+    }
+
+    pub fn includes_year(&self, year: u32) -> bool {
+        match self.year {
+            Some(y) => y == year,
+            None => true,
+        }
+    }
+
+    pub fn includes_day(&self, day: u32) -> bool {
+        match &self.days {
+            Some(days) => days.contains(&day),
+            None => true,
+        }
+    }
+
+    pub fn includes_part(&self, part: u32) -> bool {
+        match self.part {
+            Some(p) => p == part,
+            None => true,
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.trim().parse::<u32>() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("invalid day {:?}, expected 1 through 25", s)),
+    }
+}
+
+fn parse_day_range(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let mut parts = s.splitn(2, '-');
+    let start = parse_day(parts.next().unwrap())?;
+    let end = match parts.next() {
+        Some(end) => parse_day(end)?,
+        None => start,
+    };
+    if start > end {
+        return Err(format!("invalid day range {:?}", s));
+    }
+    Ok(start..=end)
+}
+
+#[cfg(test)]
+fn parse_args(args: &[&str]) -> Result<Options, String> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn test_parse_options() {
+    assert_eq!(parse_args(&[]), Ok(Options::default()));
+    assert_eq!(parse_args(&["--day", "15", "--part", "2"]),
+        Ok(Options { days: Some(15..=15), part: Some(2), ..Options::default() }));
+    assert_eq!(parse_args(&["--day", "3-7"]),
+        Ok(Options { days: Some(3..=7), ..Options::default() }));
+    assert_eq!(parse_args(&["--all"]),
+        Ok(Options { all: true, ..Options::default() }));
+    assert_eq!(parse_args(&["--input", "-"]),
+        Ok(Options { input: Some("-".to_string()), ..Options::default() }));
+    assert_eq!(parse_args(&["--all", "--check"]),
+        Ok(Options { all: true, check: true, ..Options::default() }));
+    assert_eq!(parse_args(&["--offline"]),
+        Ok(Options { offline: true, ..Options::default() }));
+    assert_eq!(parse_args(&["--year", "2019", "--day", "1"]),
+        Ok(Options { year: Some(2019), days: Some(1..=1), ..Options::default() }));
+    assert!(parse_args(&["--year", "18"]).is_err());
+    assert_eq!(parse_args(&["submit", "--day", "5", "--part", "1"]),
+        Ok(Options { days: Some(5..=5), part: Some(1), submit: true, ..Options::default() }));
+    assert!(parse_args(&["submit", "--day", "5"]).is_err());
+    assert!(parse_args(&["submit", "--day", "5", "--part", "1", "--offline"]).is_err());
+    assert!(parse_args(&["--day", "5", "submit"]).is_err());
+    assert_eq!(parse_args(&["--bench", "10", "--csv", "bench.csv"]),
+        Ok(Options { bench: Some(10), csv: Some("bench.csv".to_string()), ..Options::default() }));
+    assert!(parse_args(&["--bench", "0"]).is_err());
+    assert!(parse_args(&["--csv", "bench.csv"]).is_err());
+    assert_eq!(parse_args(&["--format", "json"]),
+        Ok(Options { format: Format::Json, ..Options::default() }));
+    assert!(parse_args(&["--format", "xml"]).is_err());
+    assert!(parse_args(&["--day"]).is_err());
+    assert!(parse_args(&["--input"]).is_err());
+    assert!(parse_args(&["--day", "26"]).is_err());
+    assert!(parse_args(&["--day", "7-3"]).is_err());
+    assert!(parse_args(&["--part", "3"]).is_err());
+    assert!(parse_args(&["--all", "--day", "1"]).is_err());
+    assert!(parse_args(&["--bogus"]).is_err());
+}
+
+#[test]
+fn test_options_selection() {
+    let options = parse_args(&["--year", "2018", "--day", "3-7", "--part", "1"]).unwrap();
+    assert!(options.includes_year(2018));
+    assert!(!options.includes_year(2019));
+    assert!(!options.includes_day(2));
+    assert!(options.includes_day(3));
+    assert!(options.includes_day(7));
+    assert!(!options.includes_day(8));
+    assert!(options.includes_part(1));
+    assert!(!options.includes_part(2));
+    assert_eq!(options.single_day(), None);
+    assert_eq!(parse_args(&["--day", "4"]).unwrap().single_day(), Some(4));
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use crate::answers::Check;
+use crate::bench::Stats;
+use crate::error::Error;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+// The outcome of running a single part, ready to be printed in any Format.
+pub struct Report {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub duration: Duration,
+    pub result: Result<String, Error>,
+    pub check: Option<Check>,
+    pub stats: Option<Stats>,
+}
+
+impl Report {
+    pub fn status(&self) -> &'static str {
+        match (&self.result, &self.check) {
+            (Err(_), _) => "error",
+            (Ok(_), Some(Check::Fail(_))) => "mismatch",
+            (Ok(_), _) => "ok",
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.status() != "ok"
+    }
+
+    pub fn print(&self, format: Format) {
+        match format {
+            Format::Text => match &self.result {
+                Ok(_) => println!("{}", self.to_text()),
+                Err(_) => eprintln!("{}", self.to_text()),
+            },
+            Format::Json => println!("{}", self.to_json()),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let output = match &self.result {
+            Ok(output) => output,
+            Err(err) => return format!("Error in day {}, part {}: {}", self.day, self.part, err),
+        };
+        let mut text = format!("Answer to day {}, part {} ({}.{:03} s): {}",
+            self.day, self.part, self.duration.as_secs(), self.duration.subsec_millis(), output);
+        if let Some(check) = &self.check {
+            text += &format!(" [{}]", check);
+        }
+        if let Some(stats) = &self.stats {
+            text += &format!("\n    {}", stats);
+        }
+        text
+    }
+
+    // Renders the report as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            format!("\"year\":{}", self.year),
+            format!("\"day\":{}", self.day),
+            format!("\"part\":{}", self.part),
+        ];
+        match &self.result {
+            Ok(output) => fields.push(format!("\"answer\":{}", json_string(output))),
+            Err(err) => {
+                fields.push("\"answer\":null".to_string());
+                fields.push(format!("\"error\":{}", json_string(&err.to_string())));
+            }
+        }
+        fields.push(format!("\"duration_ns\":{}", self.duration.as_secs() * 1_000_000_000 + u64::from(self.duration.subsec_nanos())));
+        fields.push(format!("\"status\":\"{}\"", self.status()));
+        if let Some(Check::Fail(expected)) = &self.check {
+            fields.push(format!("\"expected\":{}", json_string(expected)));
+        }
+        if let Some(stats) = &self.stats {
+            fields.push(format!("\"bench\":{{\"runs\":{},\"min_us\":{:.3},\"median_us\":{:.3},\"mean_us\":{:.3},\"stddev_us\":{:.3}}}",
+                stats.runs, stats.min, stats.median, stats.mean, stats.stddev));
+        }
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+fn test_report(result: Result<String, Error>, check: Option<Check>) -> Report {
+    Report { year: 2018, day: 10, part: 1, duration: Duration::from_nanos(1_500_000), result, check, stats: None }
+}
+
+#[test]
+fn test_json_string() {
+    assert_eq!(json_string("abc"), "\"abc\"");
+    assert_eq!(json_string("\n#.\"\\\u{1}"), "\"\\n#.\\\"\\\\\\u0001\"");
+}
+
+#[test]
+fn test_report_to_json() {
+    assert_eq!(test_report(Ok("\n##".to_string()), None).to_json(),
+        "{\"year\":2018,\"day\":10,\"part\":1,\"answer\":\"\\n##\",\"duration_ns\":1500000,\"status\":\"ok\"}");
+    assert_eq!(test_report(Ok("1".to_string()), Some(Check::Fail("2".to_string()))).to_json(),
+        "{\"year\":2018,\"day\":10,\"part\":1,\"answer\":\"1\",\"duration_ns\":1500000,\"status\":\"mismatch\",\"expected\":\"2\"}");
+    assert_eq!(test_report(Err(Error::new("oops").at_line(3, "x")), None).to_json(),
+        "{\"year\":2018,\"day\":10,\"part\":1,\"answer\":null,\"error\":\"input line 3 (\\\"x\\\"): oops\",\"duration_ns\":1500000,\"status\":\"error\"}");
+}
+
+#[test]
+fn test_report_to_text() {
+    assert_eq!(test_report(Ok("42".to_string()), None).to_text(), "Answer to day 10, part 1 (0.001 s): 42");
+    assert_eq!(test_report(Ok("42".to_string()), Some(Check::Pass)).to_text(), "Answer to day 10, part 1 (0.001 s): 42 [PASS]");
+    assert_eq!(test_report(Err(Error::new("oops")), None).to_text(), "Error in day 10, part 1: oops");
+}
@@ -0,0 +1,358 @@
+use crate::geom::{Matrix, Point, Rect};
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::mem;
+use std::ops::Add;
+
+// The outcome of a search: the exact distance to, and predecessor of, every state whose distance
+// is known. For bfs, that is every state that was discovered, including those still queued when
+// the search stopped at a goal. For dijkstra and astar, a queued state's distance may still improve,
+// so only states that were expanded are included.
+pub struct SearchResult<S, C> {
+    start: S,
+    distances: HashMap<S, C>,
+    predecessors: HashMap<S, S>,
+    goal: Option<S>,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> SearchResult<S, C> {
+    pub fn start(&self) -> &S {
+        &self.start
+    }
+
+    // The first state for which the goal predicate returned true, if any.
+    pub fn goal(&self) -> Option<&S> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_distance(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.distances.get(state).cloned()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, C> {
+        &self.distances
+    }
+
+    pub fn predecessor(&self, state: &S) -> Option<&S> {
+        self.predecessors.get(state)
+    }
+
+    pub fn predecessors(&self) -> &HashMap<S, S> {
+        &self.predecessors
+    }
+
+    // The states on the shortest path from the start to state, both inclusive.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        while let Some(predecessor) = self.predecessors.get(path.last().unwrap()) {
+            path.push(predecessor.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Breadth-first search, where every step costs 1. Stops as soon as a state matching is_goal is
+// reached; pass `|_| false` to explore everything reachable.
+pub fn bfs<S, I, F, G>(start: S, mut successors: F, mut is_goal: G) -> SearchResult<S, usize>
+    where S: Hash + Eq + Clone, I: IntoIterator<Item=S>, F: FnMut(&S) -> I, G: FnMut(&S) -> bool
+{
+    let mut result = SearchResult { start: start.clone(), distances: HashMap::new(), predecessors: HashMap::new(), goal: None };
+    let mut queue = VecDeque::new();
+    result.distances.insert(start.clone(), 0);
+    queue.push_back(start);
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+        let distance = result.distances[&state] + 1;
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = result.distances.entry(next.clone()) {
+                entry.insert(distance);
+                result.predecessors.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    result
+}
+
+// Dijkstra's algorithm. The successors closure yields each next state with the cost of the step
+// to it, which must not be negative. Stops as soon as a state matching is_goal is expanded.
+pub fn dijkstra<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> SearchResult<S, C>
+    where S: Hash + Eq + Clone, C: Copy + Ord + Default + Add<Output=C>,
+          I: IntoIterator<Item=(S, C)>, F: FnMut(&S) -> I, G: FnMut(&S) -> bool
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+// A* search: like Dijkstra, but states are expanded in order of their distance plus heuristic,
+// which must never overestimate the remaining distance to a goal, and must be consistent.
+pub fn astar<S, C, I, F, H, G>(start: S, mut successors: F, mut heuristic: H, mut is_goal: G) -> SearchResult<S, C>
+    where S: Hash + Eq + Clone, C: Copy + Ord + Default + Add<Output=C>,
+          I: IntoIterator<Item=(S, C)>, F: FnMut(&S) -> I, H: FnMut(&S) -> C, G: FnMut(&S) -> bool
+{
+    let mut result = SearchResult { start: start.clone(), distances: HashMap::new(), predecessors: HashMap::new(), goal: None };
+    // Queued states with their tentative distance and predecessor. The heap refers to them by
+    // index, so that states need not be Ord, and ties are broken first in, first out. Each entry
+    // is popped exactly once, so it is moved out then.
+    let mut entries = vec![Some((start.clone(), C::default(), None))];
+    // The best distance found so far for every discovered state, and whether it has been
+    // expanded, i.e. whether that distance is final.
+    let mut best = HashMap::new();
+    best.insert(start.clone(), (C::default(), false));
+    let mut queue = BinaryHeap::new();
+    queue.push((Reverse(heuristic(&start)), Reverse(0)));
+    while let Some((_, Reverse(index))) = queue.pop() {
+        let (state, distance, predecessor) = entries[index].take().unwrap();
+        match best.get_mut(&state) {
+            Some((best_distance, expanded)) if !*expanded && *best_distance == distance => *expanded = true,
+            _ => continue,
+        }
+        result.distances.insert(state.clone(), distance);
+        if let Some(predecessor) = predecessor {
+            result.predecessors.insert(state.clone(), predecessor);
+        }
+        if is_goal(&state) {
+            result.goal = Some(state);
+            break;
+        }
+        for (next, cost) in successors(&state) {
+            let next_distance = distance + cost;
+            match best.entry(next.clone()) {
+                Entry::Occupied(entry) if entry.get().1 || entry.get().0 <= next_distance => continue,
+                Entry::Occupied(mut entry) => { entry.insert((next_distance, false)); }
+                Entry::Vacant(entry) => { entry.insert((next_distance, false)); }
+            }
+            queue.push((Reverse(next_distance + heuristic(&next)), Reverse(entries.len())));
+            entries.push(Some((next, next_distance, Some(state.clone()))));
+        }
+    }
+    result
+}
+
+// The result of GridSearch::nearest_targets: all targets at the smallest distance from the start,
+// and the distances needed to find every first step towards each of them.
+pub struct NearestTargets<'a, O> {
+    distance: u32,
+    targets: Vec<Point>,
+    // Distances from the start, up to the distance of the targets; u32::MAX where not reached.
+    distances: &'a Matrix<u32>,
+    tie_break: O,
+}
+
+impl<O: Fn(&Point, &Point) -> Ordering> NearestTargets<'_, O> {
+    pub fn distance(&self) -> u32 {
+        self.distance
+    }
+
+    // Sorted by the tie-break ordering.
+    pub fn targets(&self) -> &[Point] {
+        &self.targets
+    }
+
+    // The neighbors of the start that begin a shortest path to target, which must be one of the
+    // targets, sorted by the tie-break ordering. Empty if the start is a target itself.
+    pub fn first_steps(&self, target: Point) -> Vec<Point> {
+        if self.distance == 0 {
+            return vec![];
+        }
+        // Walk back one layer at a time; each cell's predecessors are its neighbors one closer.
+        let mut layer = vec![target];
+        let mut previous = vec![];
+        for distance in (1..self.distance).rev() {
+            previous.clear();
+            for point in &layer {
+                for &neighbor in &point.neighbors() {
+                    if self.distances.get(neighbor) == Some(&distance) {
+                        previous.push(neighbor);
+                    }
+                }
+            }
+            // Paths converge, so the same cell can be reached from several cells of the layer.
+            previous.sort_unstable();
+            previous.dedup();
+            mem::swap(&mut layer, &mut previous);
+        }
+        // The layer is sorted by Point::cmp, so steps that the tie-break can't tell apart keep a
+        // fixed order.
+        layer.sort_by(&self.tie_break);
+        layer
+    }
+
+    // The first target in tie-break order, and the first step towards it in tie-break order.
+    pub fn best(&self) -> (Point, Option<Point>) {
+        let target = self.targets[0];
+        (target, self.first_steps(target).first().cloned())
+    }
+}
+
+// The default tie-break ordering of nearest_targets, Point::cmp.
+pub type ReadingOrder = fn(&Point, &Point) -> Ordering;
+
+// Breadth-first search on a grid, moving orthogonally between passable cells within bounds, for
+// the nearest cells matching is_target. Unlike bfs, this finds all shortest paths, so ties can be
+// broken by an explicit ordering rather than by the order in which neighbors happen to be visited.
+// The distance grid is kept between searches, so that repeated searches within the same bounds
+// don't have to allocate it again.
+#[derive(Clone)]
+pub struct GridSearch {
+    // u32::MAX for cells that the last search did not reach.
+    distances: Matrix<u32>,
+    // The cells that the last search reached, in order of distance, so that only those need to be
+    // reset before the next one.
+    visited: Vec<Point>,
+}
+
+impl GridSearch {
+    pub fn new(bounds: &Rect) -> GridSearch {
+        GridSearch { distances: Matrix::new(bounds, u32::MAX), visited: vec![] }
+    }
+
+    // The start need not be passable. Ties are broken in reading order.
+    pub fn nearest_targets<P, G>(&mut self, start: Point, is_passable: P, is_target: G) -> Option<NearestTargets<'_, ReadingOrder>>
+        where P: FnMut(Point) -> bool, G: FnMut(Point) -> bool
+    {
+        self.nearest_targets_by(start, is_passable, is_target, Point::cmp)
+    }
+
+    // Like nearest_targets, with ties broken by the given ordering.
+    pub fn nearest_targets_by<P, G, O>(&mut self, start: Point, mut is_passable: P, mut is_target: G, tie_break: O) -> Option<NearestTargets<'_, O>>
+        where P: FnMut(Point) -> bool, G: FnMut(Point) -> bool, O: Fn(&Point, &Point) -> Ordering
+    {
+        for &pos in &self.visited {
+            self.distances[pos] = u32::MAX;
+        }
+        self.visited.clear();
+        self.distances[start] = 0;
+        self.visited.push(start);
+        let mut distance = 0;
+        let mut targets = vec![];
+        if is_target(start) {
+            targets.push(start);
+        }
+        // The cells at the current distance are the tail of visited, from layer_start on. Finish
+        // the layer in which the first target is found, so that all tied targets are seen.
+        let mut layer_start = 0;
+        while targets.is_empty() && layer_start < self.visited.len() {
+            distance += 1;
+            let layer_end = self.visited.len();
+            for i in layer_start..layer_end {
+                for &next in &self.visited[i].neighbors() {
+                    if self.distances.get(next) == Some(&u32::MAX) && is_passable(next) {
+                        self.distances[next] = distance;
+                        if is_target(next) {
+                            targets.push(next);
+                        }
+                        self.visited.push(next);
+                    }
+                }
+            }
+            layer_start = layer_end;
+        }
+        if targets.is_empty() {
+            return None;
+        }
+        targets.sort_by(&tie_break);
+        Some(NearestTargets { distance, targets, distances: &self.distances, tie_break })
+    }
+}
+
+#[cfg(test)]
+fn line_successors(&n: &i32) -> Vec<(i32, u32)> {
+    // Walking costs 3 per step, but from multiples of 5 you can jump 5 ahead for 1.
+    let mut next = vec![(n - 1, 3), (n + 1, 3)];
+    if n % 5 == 0 {
+        next.push((n + 5, 1));
+    }
+    next
+}
+
+#[test]
+fn test_bfs() {
+    let result = bfs(0, |&n: &i32| vec![n + 1, n * 2], |&n| n == 10);
+    assert_eq!(result.goal(), Some(&10));
+    assert_eq!(result.goal_distance(), Some(5));
+    assert_eq!(result.path_to(&10), Some(vec![0, 1, 2, 4, 5, 10]));
+    assert_eq!(result.path_to(&1000), None);
+    // 16 = 8 * 2 was still queued when the goal was reached.
+    assert_eq!(result.distance(&16), Some(5));
+
+    let result = bfs(0, |&n: &i32| if n < 5 { vec![n + 1] } else { vec![] }, |_| false);
+    assert_eq!(result.goal(), None);
+    assert_eq!(result.distances().len(), 6);
+    assert_eq!(result.distance(&5), Some(5));
+    assert_eq!(result.predecessor(&5), Some(&4));
+    assert_eq!(result.predecessor(&0), None);
+}
+
+#[test]
+fn test_dijkstra() {
+    let result = dijkstra(0, line_successors, |&n| n == 12);
+    // 0 -> 5 -> 10 by jumping, then two steps of walking.
+    assert_eq!(result.goal_distance(), Some(8));
+    assert_eq!(result.path_to(&12), Some(vec![0, 5, 10, 11, 12]));
+    assert_eq!(result.distance(&-1), Some(3));
+    // 13 was queued via 15 and 14 at distance 9, but not expanded before the goal.
+    assert_eq!(result.distance(&13), None);
+    assert_eq!(result.start(), &0);
+}
+
+#[test]
+fn test_astar() {
+    // A heuristic of the remaining distance in jumps is admissible and consistent here.
+    let result = astar(0, line_successors, |&n| (12 - n).unsigned_abs() / 5, |&n| n == 12);
+    assert_eq!(result.goal_distance(), Some(8));
+    assert_eq!(result.path_to(&12), Some(vec![0, 5, 10, 11, 12]));
+    // The heuristic keeps the search from wandering off in the negative direction.
+    assert!(result.distances().len() < dijkstra(0, line_successors, |&n| n == 12).distances().len());
+}
+
+#[test]
+fn test_nearest_targets() {
+    let map = Matrix::parse("#######\n#E..T.#\n#...#.#\n#.T.#T#\n#######", Point::origin(), b'#');
+    let is_passable = |pos: Point| map[pos] != b'#';
+    let is_target = |pos: Point| map[pos] == b'T';
+    let mut search = GridSearch::new(map.rect());
+    let nearest = search.nearest_targets(Point::new(1, 1), is_passable, is_target).unwrap();
+    assert_eq!(nearest.distance(), 3);
+    // Both T's at distance 3, in reading order.
+    assert_eq!(nearest.targets(), &[Point::new(4, 1), Point::new(2, 3)]);
+    assert_eq!(nearest.first_steps(Point::new(4, 1)), vec![Point::new(2, 1)]);
+    assert_eq!(nearest.first_steps(Point::new(2, 3)), vec![Point::new(2, 1), Point::new(1, 2)]);
+    assert_eq!(nearest.best(), (Point::new(4, 1), Some(Point::new(2, 1))));
+
+    // Prefer targets and steps that are lowest, then leftmost.
+    let nearest = search.nearest_targets_by(Point::new(1, 1), is_passable, is_target, |a, b| b.y.cmp(&a.y).then(a.x.cmp(&b.x))).unwrap();
+    assert_eq!(nearest.best(), (Point::new(2, 3), Some(Point::new(1, 2))));
+
+    let nearest = search.nearest_targets(Point::new(4, 1), is_passable, is_target).unwrap();
+    assert_eq!((nearest.distance(), nearest.best()), (0, (Point::new(4, 1), None)));
+    assert!(search.nearest_targets(Point::new(1, 1), is_passable, |_| false).is_none());
+}
+
+#[test]
+fn test_nearest_targets_partial_tie_break() {
+    // Many shortest paths converge on the same cells; comparing only y must not leave duplicates.
+    let bounds = Rect::from_inclusive_ranges(0 ..= 6, 0 ..= 6);
+    let target = Point::new(5, 5);
+    let mut search = GridSearch::new(&bounds);
+    let nearest = search.nearest_targets_by(Point::origin(), |_| true, |pos| pos == target, |a, b| a.y.cmp(&b.y)).unwrap();
+    assert_eq!(nearest.distance(), 10);
+    assert_eq!(nearest.first_steps(target), vec![Point::new(1, 0), Point::new(0, 1)]);
+
+    // Steps that the tie-break considers equal come in reading order.
+    let nearest = search.nearest_targets_by(Point::new(3, 3), |_| true, |pos| pos == target, |_, _| Ordering::Equal).unwrap();
+    assert_eq!(nearest.distance(), 4);
+    assert_eq!(nearest.first_steps(target), vec![Point::new(4, 3), Point::new(3, 4)]);
+}
@@ -0,0 +1,165 @@
+use lazy_static::lazy_static;
+
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::answers::escape;
+use crate::input::{InputError, InputSource, load_session_cookie, year_file_names};
+
+// The outcome of submitting an answer, as far as we can tell from the response page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    // Contains the remaining wait time as reported by the server, e.g. "4m 30s".
+    RateLimited(Option<String>),
+    // The part was already solved, or part 1 must be solved first.
+    WrongLevel,
+    // Not submitted, because the server has rejected this exact answer before.
+    KnownWrong,
+    Unrecognized,
+}
+
+impl Verdict {
+    pub fn parse(page: &str) -> Verdict {
+        lazy_static! {
+            static ref WAIT_RE: Regex = Regex::new(r"You have (?P<wait>[^.]*?) left to wait").unwrap();
+        }
+        if page.contains("That's the right answer") {
+            Verdict::Correct
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if page.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if page.contains("You gave an answer too recently") {
+            Verdict::RateLimited(WAIT_RE.captures(page).map(|captures| captures["wait"].to_string()))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unrecognized
+        }
+    }
+
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow | Verdict::KnownWrong)
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::RateLimited(Some(wait)) => write!(f, "rate limited, wait {}", wait),
+            Verdict::RateLimited(None) => write!(f, "rate limited"),
+            Verdict::WrongLevel => write!(f, "not submitted for this part; is it already solved?"),
+            Verdict::KnownWrong => write!(f, "already known to be wrong, not submitted"),
+            Verdict::Unrecognized => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+// Answers that were rejected before are kept in input/YYYY/NN.wrong, one per line, as the part
+// number followed by a space and the answer with newlines escaped.
+fn wrong_answers_file_name(source: &InputSource, year: u32, day: u32) -> PathBuf {
+    year_file_names(&source.cache_dir, year, &format!("{:02}.wrong", day)).remove(0)
+}
+
+fn wrong_answer_line(part: u32, answer: &str) -> String {
+    format!("{} {}", part, escape(answer))
+}
+
+fn is_known_wrong(source: &InputSource, year: u32, day: u32, part: u32, answer: &str) -> Result<bool, io::Error> {
+    let line = wrong_answer_line(part, answer);
+    match fs::read_to_string(wrong_answers_file_name(source, year, day)) {
+        Ok(contents) => Ok(contents.lines().any(|l| l == line)),
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+fn record_wrong(source: &InputSource, year: u32, day: u32, part: u32, answer: &str) -> Result<(), io::Error> {
+    let file_name = wrong_answers_file_name(source, year, day);
+    fs::create_dir_all(file_name.parent().unwrap())?;
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(&file_name)?;
+    writeln!(file, "{}", wrong_answer_line(part, answer))
+}
+
+// Submits an answer, unless it is already known to be wrong. Answers that turn out to be wrong
+// are recorded so they will not be submitted again.
+pub fn submit(source: &InputSource, year: u32, day: u32, part: u32, answer: &str) -> Result<Verdict, InputError> {
+    let wrong_answers_file_name = wrong_answers_file_name(source, year, day);
+    if is_known_wrong(source, year, day, part, answer).map_err(|err| InputError::Read(wrong_answers_file_name.clone(), err))? {
+        return Ok(Verdict::KnownWrong);
+    }
+    let url = format!("{}/{}/day/{}/answer", source.base_url, year, day);
+    let session_cookie = load_session_cookie(&source.session_cookie_file)?;
+    let mut response = reqwest::Client::new()
+        .post(&url)
+        .header(reqwest::header::COOKIE, format!("session={}", session_cookie))
+        .form(&[("level", part.to_string()), ("answer", answer.to_string())])
+        .send()
+        .map_err(InputError::Network)?;
+    if !response.status().is_success() {
+        return Err(InputError::HttpStatus(response.status()));
+    }
+    let verdict = Verdict::parse(&response.text().map_err(InputError::Network)?);
+    if verdict.is_wrong() {
+        record_wrong(source, year, day, part, answer)
+            .map_err(|err| InputError::Write(wrong_answers_file_name, err))?;
+    }
+    Ok(verdict)
+}
+
+#[test]
+fn test_verdict_parse() {
+    assert_eq!(Verdict::parse("<p>That's the right answer!  You are one gold star closer.</p>"), Verdict::Correct);
+    assert_eq!(Verdict::parse("<p>That's not the right answer.  If you're stuck, ...</p>"), Verdict::Wrong);
+    assert_eq!(Verdict::parse("<p>That's not the right answer; your answer is too high.</p>"), Verdict::TooHigh);
+    assert_eq!(Verdict::parse("<p>That's not the right answer; your answer is too low.</p>"), Verdict::TooLow);
+    assert_eq!(
+        Verdict::parse("<p>You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 30s left to wait.</p>"),
+        Verdict::RateLimited(Some("4m 30s".to_string())));
+    assert_eq!(Verdict::parse("<p>You don't seem to be solving the right level.  Did you already complete it?</p>"), Verdict::WrongLevel);
+    assert_eq!(Verdict::parse("<html></html>"), Verdict::Unrecognized);
+}
+
+#[test]
+fn test_submit_records_wrong_answers() {
+    use crate::input::{serve_once, test_source};
+
+    let (base_url, server) = serve_once("200 OK", "That's not the right answer; your answer is too high.");
+    let source = test_source("submit-wrong", &base_url);
+    assert_eq!(submit(&source, 2018, 3, 2, "1234").unwrap(), Verdict::TooHigh);
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /2018/day/3/answer "));
+    assert!(request.to_lowercase().contains("cookie: session=secret\r\n"));
+    assert!(request.ends_with("level=2&answer=1234"));
+
+    // The server is gone now, so this must not attempt another request.
+    assert_eq!(submit(&source, 2018, 3, 2, "1234").unwrap(), Verdict::KnownWrong);
+    assert_eq!(fs::read_to_string(source.cache_dir.join("2018/03.wrong")).unwrap(), "2 1234\n");
+}
+
+#[test]
+fn test_submit_correct() {
+    use crate::input::{serve_once, test_source};
+
+    let (base_url, server) = serve_once("200 OK", "That's the right answer!");
+    let source = test_source("submit-correct", &base_url);
+    assert_eq!(submit(&source, 2018, 4, 1, "42").unwrap(), Verdict::Correct);
+    server.join().unwrap();
+    assert!(!source.cache_dir.join("2018/04.wrong").exists());
+}